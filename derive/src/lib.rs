@@ -0,0 +1,511 @@
+//! Derives the owned counterpart of a borrowing adapter.
+//!
+//! Given an adapter such as `Oriented<'a, T>`, which borrows its parent and is built by a
+//! constructor taking `&'a mut T` followed by some arguments, the derive generates
+//!
+//! * the owned tuple struct `OrientedT<T>(T, ...)` holding the parent and the arguments, with
+//!   `inner`, `inner_mut` and `into_inner` accessors,
+//! * its `Transformer` impl, whose GAT is the borrowing adapter, and
+//! * an `OwnedOrientedExt` extension trait providing `owned_oriented(...)`.
+//!
+//! `DrawTargetTransformer` targets `graphics::Transformer` and `I2cTransformer` targets
+//! `serial::Transformer`; both are re-exported there as `Transformer`.
+//!
+//! ```ignore
+//! #[derive(graphics::Transformer)]
+//! #[transformer(args(orientation: Orientation))]
+//! pub struct Oriented<'a, T> {
+//!     parent: &'a mut T,
+//!     orientation: Orientation,
+//! }
+//! ```
+//!
+//! Options of the `#[transformer(...)]` attribute:
+//!
+//! * `args(name: Type, ...)` — constructor arguments following the parent. Arguments taken by
+//!   reference are stored by value (cloned) and passed back by reference, all others are cloned
+//!   on every `transform()`.
+//! * `constructor = path` — defaults to `Adapter::new`.
+//! * `adapter = path` — the borrowing adapter, when it is defined in another crate and the derive
+//!   is attached to a stand-in with the same generics. Names default to those of the adapter.
+//! * `parent = T` — the type parameter of the parent, defaults to the first one.
+//! * `bounds(T: Bound, ...)` — extra predicates for the generated impls. Associated types of the
+//!   parent must be qualified, e.g. `<T as ErrorType>::Error`.
+//! * `name = AdapterT`, `ext = OwnedAdapterExt`, `owned = owned_adapter` — generated names.
+//! * `module = path` — where the transformer trait and `Owned` live, defaults to
+//!   `crate::graphics` or `crate::serial`.
+//! * `color = Type`, `error = Type` — draw targets only, default to those of the parent.
+//! * `bounding_box = path` — draw targets only, a function taking references to the parent and
+//!   to every argument and returning the `Option<Rectangle>` of `Transformer::bounding_box`.
+//! * `address_mode = Type`, `error = Type` — I2C only, default to 7-bit addresses and the
+//!   parent's error type.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Group, Span, TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote, DeriveInput, GenericParam, Ident, Index, Lifetime, Path, Token,
+    Type, WherePredicate,
+};
+
+#[proc_macro_derive(DrawTargetTransformer, attributes(transformer))]
+pub fn draw_target_transformer(input: TokenStream) -> TokenStream {
+    expand(parse_macro_input!(input as DeriveInput), Kind::Graphics)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(I2cTransformer, attributes(transformer))]
+pub fn i2c_transformer(input: TokenStream) -> TokenStream {
+    expand(parse_macro_input!(input as DeriveInput), Kind::Serial)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Kind {
+    Graphics,
+    Serial,
+}
+
+struct Arg {
+    name: Ident,
+    ty: Type,
+}
+
+impl Arg {
+    /// The type stored in the owned struct.
+    fn stored(&self) -> &Type {
+        match &self.ty {
+            Type::Reference(reference) => &reference.elem,
+            ty => ty,
+        }
+    }
+
+    /// How `transform()` hands the stored value at `index` to the constructor.
+    fn passed(&self, index: Index) -> TokenStream2 {
+        match &self.ty {
+            Type::Reference(_) => quote!(&self.#index),
+            _ => quote!(::core::clone::Clone::clone(&self.#index)),
+        }
+    }
+
+    /// How the extension method turns the argument into the stored value.
+    fn owned(&self) -> TokenStream2 {
+        let name = &self.name;
+
+        match &self.ty {
+            Type::Reference(_) => quote!(::core::clone::Clone::clone(#name)),
+            _ => quote!(#name),
+        }
+    }
+}
+
+impl Parse for Arg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty = input.parse()?;
+
+        Ok(Self { name, ty })
+    }
+}
+
+#[derive(Default)]
+struct Options {
+    args: Vec<Arg>,
+    bounds: Vec<WherePredicate>,
+    constructor: Option<Path>,
+    adapter: Option<Path>,
+    parent: Option<Ident>,
+    name: Option<Ident>,
+    ext: Option<Ident>,
+    owned: Option<Ident>,
+    module: Option<Path>,
+    color: Option<Type>,
+    error: Option<Type>,
+    bounding_box: Option<Path>,
+    address_mode: Option<Type>,
+}
+
+impl Options {
+    fn from_input(input: &DeriveInput, kind: Kind) -> syn::Result<Self> {
+        let mut options = Self::default();
+
+        for attr in input
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("transformer"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("args") {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    options
+                        .args
+                        .extend(content.parse_terminated(Arg::parse, Token![,])?);
+                } else if meta.path.is_ident("bounds") {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    options
+                        .bounds
+                        .extend(content.parse_terminated(WherePredicate::parse, Token![,])?);
+                } else if meta.path.is_ident("constructor") {
+                    options.constructor = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("adapter") {
+                    options.adapter = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("parent") {
+                    options.parent = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("name") {
+                    options.name = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("ext") {
+                    options.ext = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("owned") {
+                    options.owned = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("module") {
+                    options.module = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("error") {
+                    options.error = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("color") && kind == Kind::Graphics {
+                    options.color = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("bounding_box") && kind == Kind::Graphics {
+                    options.bounding_box = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("address_mode") && kind == Kind::Serial {
+                    options.address_mode = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("unsupported transformer option"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(options)
+    }
+}
+
+fn expand(input: DeriveInput, kind: Kind) -> syn::Result<TokenStream2> {
+    let options = Options::from_input(&input, kind)?;
+    let vis = &input.vis;
+    let adapter_path: Path = options
+        .adapter
+        .clone()
+        .unwrap_or_else(|| input.ident.clone().into());
+    let adapter = match adapter_path.segments.last() {
+        Some(segment) if segment.arguments.is_none() => segment.ident.clone(),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &adapter_path,
+                "expected the path of the adapter, without generics",
+            ))
+        }
+    };
+
+    let mut lifetimes = input.generics.lifetimes();
+    let lifetime = match (lifetimes.next(), lifetimes.next()) {
+        (Some(param), None) => param.lifetime.clone(),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.generics,
+                "expected exactly one lifetime, the borrow of the parent",
+            ))
+        }
+    };
+
+    if let Some(param) = input.generics.const_params().next() {
+        return Err(syn::Error::new_spanned(
+            param,
+            "const parameters are not supported",
+        ));
+    }
+
+    let parent = match (&options.parent, input.generics.type_params().next()) {
+        (Some(parent), _) => parent.clone(),
+        (None, Some(param)) => param.ident.clone(),
+        (None, None) => {
+            return Err(syn::Error::new_spanned(
+                &input.generics,
+                "expected a type parameter for the parent",
+            ))
+        }
+    };
+
+    if !input
+        .generics
+        .type_params()
+        .any(|param| param.ident == parent)
+    {
+        return Err(syn::Error::new_spanned(
+            &parent,
+            "not a type parameter of the adapter",
+        ));
+    }
+
+    let extra: Vec<&Ident> = input
+        .generics
+        .type_params()
+        .map(|param| &param.ident)
+        .filter(|ident| **ident != parent)
+        .collect();
+
+    // Everything the adapter requires of its parameters, minus what refers to the borrow.
+    let mut predicates: Vec<WherePredicate> = input
+        .generics
+        .type_params()
+        .filter(|param| !param.bounds.is_empty())
+        .map(|param| {
+            let (ident, bounds) = (&param.ident, &param.bounds);
+            parse_quote!(#ident: #bounds)
+        })
+        .chain(
+            input
+                .generics
+                .where_clause
+                .iter()
+                .flat_map(|clause| clause.predicates.iter().cloned()),
+        )
+        .filter(|predicate| !mentions_lifetime(predicate.to_token_stream(), &lifetime))
+        .collect();
+
+    predicates.extend(options.bounds.iter().cloned());
+    predicates.push(match kind {
+        Kind::Graphics => parse_quote!(#parent: ::embedded_graphics::draw_target::DrawTarget),
+        Kind::Serial => parse_quote!(#parent: ::embedded_hal::i2c::ErrorType),
+    });
+
+    let module = options.module.clone().unwrap_or_else(|| match kind {
+        Kind::Graphics => parse_quote!(crate::graphics),
+        Kind::Serial => parse_quote!(crate::serial),
+    });
+
+    let name = options
+        .name
+        .clone()
+        .unwrap_or_else(|| format_ident!("{}T", adapter));
+    let ext = options
+        .ext
+        .clone()
+        .unwrap_or_else(|| format_ident!("Owned{}Ext", adapter));
+    let owned = options
+        .owned
+        .clone()
+        .unwrap_or_else(|| format_ident!("owned_{}", snake_case(&adapter)));
+    let constructor = options
+        .constructor
+        .clone()
+        .unwrap_or_else(|| parse_quote!(#adapter_path::new));
+
+    let gat = Lifetime::new("'__transform", Span::call_site());
+    let adapter_args = input.generics.params.iter().map(|param| match param {
+        GenericParam::Lifetime(_) => gat.to_token_stream(),
+        GenericParam::Type(param) => param.ident.to_token_stream(),
+        GenericParam::Const(param) => param.ident.to_token_stream(),
+    });
+
+    let stored = options.args.iter().map(Arg::stored);
+    let passed = options
+        .args
+        .iter()
+        .enumerate()
+        .map(|(index, arg)| arg.passed(Index::from(index + 1)));
+    let arg_names: Vec<&Ident> = options.args.iter().map(|arg| &arg.name).collect();
+    let arg_types: Vec<&Type> = options.args.iter().map(|arg| &arg.ty).collect();
+    let arg_values = options.args.iter().map(Arg::owned);
+
+    // Parameters only used by the adapter's borrow are kept in a marker field
+    let unstored: Vec<&Ident> = extra
+        .iter()
+        .copied()
+        .filter(|ident| {
+            !options
+                .args
+                .iter()
+                .any(|arg| mentions_ident(arg.stored().to_token_stream(), ident))
+        })
+        .collect();
+
+    let (phantom_field, phantom_value) = if unstored.is_empty() {
+        (None, None)
+    } else {
+        (
+            Some(quote!(, ::core::marker::PhantomData<fn() -> (#(#unstored,)*)>)),
+            Some(quote!(, ::core::marker::PhantomData)),
+        )
+    };
+
+    let method_generics = if extra.is_empty() {
+        quote!()
+    } else {
+        quote!(<#(#extra),*>)
+    };
+
+    let self_predicates: Vec<TokenStream2> = predicates
+        .iter()
+        .map(|predicate| replace_ident(predicate.to_token_stream(), &parent))
+        .collect();
+
+    let bounding_box = options.bounding_box.as_ref().map(|path| {
+        let fields = (1..=options.args.len()).map(Index::from);
+
+        quote! {
+            fn bounding_box(&self) -> ::core::option::Option<::embedded_graphics::primitives::Rectangle> {
+                #path(&self.0 #(, &self.#fields)*)
+            }
+        }
+    });
+
+    let associated = match kind {
+        Kind::Graphics => {
+            let color = options.color.clone().unwrap_or_else(
+                || parse_quote!(<#parent as ::embedded_graphics::draw_target::DrawTarget>::Color),
+            );
+            let error = options.error.clone().unwrap_or_else(
+                || parse_quote!(<#parent as ::embedded_graphics::draw_target::DrawTarget>::Error),
+            );
+
+            quote! {
+                type Color = #color;
+                type Error = #error;
+
+                type DrawTarget<#gat> = #adapter_path<#(#adapter_args),*> where Self: #gat;
+
+                fn transform<#gat>(&#gat mut self) -> Self::DrawTarget<#gat> {
+                    #constructor(&mut self.0 #(, #passed)*)
+                }
+
+                #bounding_box
+            }
+        }
+        Kind::Serial => {
+            let address_mode = options
+                .address_mode
+                .clone()
+                .unwrap_or_else(|| parse_quote!(u8));
+            let error = options.error.clone().unwrap_or_else(
+                || parse_quote!(<#parent as ::embedded_hal::i2c::ErrorType>::Error),
+            );
+
+            quote! {
+                type AddressMode = #address_mode;
+                type Error = #error;
+
+                type I2c<#gat> = #adapter_path<#(#adapter_args),*> where Self: #gat;
+
+                fn transform<#gat>(&#gat mut self) -> Self::I2c<#gat> {
+                    #constructor(&mut self.0 #(, #passed)*)
+                }
+            }
+        }
+    };
+
+    let struct_doc = format!("Owned counterpart of [`{adapter}`], see [`{ext}::{owned}`].");
+    let ext_doc = format!("Provides [`{ext}::{owned}`] on every suitable parent.");
+
+    Ok(quote! {
+        #[doc = #struct_doc]
+        #vis struct #name<#parent #(, #extra)*>(#parent #(, #stored)* #phantom_field);
+
+        impl<#parent #(, #extra)*> #module::Transformer for #name<#parent #(, #extra)*>
+        where
+            #(#predicates,)*
+        {
+            #associated
+        }
+
+        impl<#parent #(, #extra)*> #name<#parent #(, #extra)*> {
+            pub fn inner(&self) -> &#parent {
+                &self.0
+            }
+
+            pub fn inner_mut(&mut self) -> &mut #parent {
+                &mut self.0
+            }
+
+            pub fn into_inner(self) -> #parent {
+                self.0
+            }
+        }
+
+        #[doc = #ext_doc]
+        #vis trait #ext: Sized {
+            fn #owned #method_generics(self #(, #arg_names: #arg_types)*)
+                -> #module::Owned<#name<Self #(, #extra)*>>
+            where
+                #(#self_predicates,)*;
+        }
+
+        impl<#parent> #ext for #parent {
+            fn #owned #method_generics(self #(, #arg_names: #arg_types)*)
+                -> #module::Owned<#name<Self #(, #extra)*>>
+            where
+                #(#self_predicates,)*
+            {
+                #module::Transformer::into_owned(#name(self #(, #arg_values)* #phantom_value))
+            }
+        }
+    })
+}
+
+fn mentions_ident(tokens: TokenStream2, ident: &Ident) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(other) => other == *ident,
+        TokenTree::Group(group) => mentions_ident(group.stream(), ident),
+        _ => false,
+    })
+}
+
+fn mentions_lifetime(tokens: TokenStream2, lifetime: &Lifetime) -> bool {
+    let mut tokens = tokens.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Group(group) if mentions_lifetime(group.stream(), lifetime) => return true,
+            TokenTree::Punct(punct) if punct.as_char() == '\'' => {
+                if matches!(tokens.peek(), Some(TokenTree::Ident(ident)) if *ident == lifetime.ident)
+                {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    false
+}
+
+/// Rewrites the parent's type parameter to `Self`, for use inside the extension trait.
+fn replace_ident(tokens: TokenStream2, from: &Ident) -> TokenStream2 {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Ident(ident) if ident == *from => {
+                TokenTree::Ident(Ident::new("Self", ident.span()))
+            }
+            TokenTree::Group(group) => {
+                let mut replaced =
+                    Group::new(group.delimiter(), replace_ident(group.stream(), from));
+                replaced.set_span(group.span());
+                TokenTree::Group(replaced)
+            }
+            token => token,
+        })
+        .collect()
+}
+
+fn snake_case(ident: &Ident) -> String {
+    let mut snake = String::new();
+
+    for (i, c) in ident.to_string().chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+
+    snake
+}
@@ -0,0 +1,7 @@
+use owned_transform_derive::DrawTargetTransformer;
+
+#[derive(DrawTargetTransformer)]
+#[transformer(adapter = Translated<'a, T>)]
+pub struct Adapter<'a, T>(core::marker::PhantomData<&'a mut T>);
+
+fn main() {}
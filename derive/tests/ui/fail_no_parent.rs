@@ -0,0 +1,8 @@
+use owned_transform_derive::DrawTargetTransformer;
+
+#[derive(DrawTargetTransformer)]
+pub struct Adapter<'a> {
+    parent: &'a mut u8,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use owned_transform_derive::DrawTargetTransformer;
+
+#[derive(DrawTargetTransformer)]
+pub struct Adapter<'a, 'b, T> {
+    parent: &'a mut T,
+    other: &'b mut T,
+}
+
+fn main() {}
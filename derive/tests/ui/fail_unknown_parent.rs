@@ -0,0 +1,9 @@
+use owned_transform_derive::I2cTransformer;
+
+#[derive(I2cTransformer)]
+#[transformer(parent = U)]
+pub struct Adapter<'a, T> {
+    parent: &'a mut T,
+}
+
+fn main() {}
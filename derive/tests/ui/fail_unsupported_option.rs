@@ -0,0 +1,10 @@
+use owned_transform_derive::DrawTargetTransformer;
+
+// `address_mode` only applies to I2C transformers
+#[derive(DrawTargetTransformer)]
+#[transformer(address_mode = u16)]
+pub struct Adapter<'a, T> {
+    parent: &'a mut T,
+}
+
+fn main() {}
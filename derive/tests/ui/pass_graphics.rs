@@ -0,0 +1,143 @@
+//! The owned transformers derived for a local and a foreign adapter.
+
+use embedded_graphics::draw_target::{DrawTarget, DrawTargetExt, Translated};
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use owned_transform_derive::DrawTargetTransformer;
+
+/// The shape of `graphics::Transformer` and `graphics::Owned`.
+mod graphics {
+    use embedded_graphics::draw_target::DrawTarget;
+    use embedded_graphics::pixelcolor::PixelColor;
+    use embedded_graphics::primitives::Rectangle;
+
+    pub trait Transformer {
+        type Color: PixelColor;
+        type Error;
+
+        type DrawTarget<'a>: DrawTarget<Color = Self::Color, Error = Self::Error>
+        where
+            Self: 'a;
+
+        fn transform<'a>(&'a mut self) -> Self::DrawTarget<'a>;
+
+        fn bounding_box(&self) -> Option<Rectangle> {
+            None
+        }
+
+        fn into_owned(self) -> Owned<Self>
+        where
+            Self: Sized,
+        {
+            Owned(self)
+        }
+    }
+
+    pub struct Owned<T>(pub T);
+}
+
+struct Display;
+
+impl DrawTarget for Display {
+    type Color = BinaryColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, _pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        Ok(())
+    }
+}
+
+impl OriginDimensions for Display {
+    fn size(&self) -> Size {
+        Size::new(4, 4)
+    }
+}
+
+#[derive(DrawTargetTransformer)]
+#[transformer(args(invert: bool, area: &Rectangle))]
+pub struct Inverted<'a, T: DrawTarget<Color = BinaryColor>> {
+    parent: &'a mut T,
+    invert: bool,
+    area: Rectangle,
+}
+
+impl<'a, T> Inverted<'a, T>
+where
+    T: DrawTarget<Color = BinaryColor>,
+{
+    pub fn new(parent: &'a mut T, invert: bool, area: &Rectangle) -> Self {
+        Self {
+            parent,
+            invert,
+            area: *area,
+        }
+    }
+}
+
+impl<T> DrawTarget for Inverted<'_, T>
+where
+    T: DrawTarget<Color = BinaryColor>,
+{
+    type Color = BinaryColor;
+    type Error = T::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let (invert, area) = (self.invert, self.area);
+
+        self.parent
+            .draw_iter(pixels.into_iter().map(|Pixel(point, color)| {
+                match invert && area.contains(point) {
+                    true => Pixel(point, color.invert()),
+                    false => Pixel(point, color),
+                }
+            }))
+    }
+}
+
+impl<T> Dimensions for Inverted<'_, T>
+where
+    T: DrawTarget<Color = BinaryColor>,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.parent.bounding_box()
+    }
+}
+
+#[derive(DrawTargetTransformer)]
+#[transformer(
+    adapter = Translated,
+    constructor = DrawTargetExt::translated,
+    args(offset: Point),
+    bounding_box = translated_bounds
+)]
+pub struct TranslatedAdapter<'a, T>(core::marker::PhantomData<&'a mut T>);
+
+fn translated_bounds<T>(parent: &T, offset: &Point) -> Option<Rectangle>
+where
+    T: DrawTarget,
+{
+    Some(parent.bounding_box().translate(-*offset))
+}
+
+fn main() {
+    use graphics::Transformer;
+
+    let area = Rectangle::new(Point::zero(), Size::new(2, 2));
+    let mut inverted = Display.owned_inverted(true, &area).0;
+    inverted.transform().clear(BinaryColor::On).unwrap();
+    let _: &Display = inverted.inner();
+
+    let translated = Display.owned_translated(Point::new(1, 2)).0;
+    assert_eq!(
+        translated.bounding_box(),
+        Some(Rectangle::new(Point::new(-1, -2), Size::new(4, 4)))
+    );
+    let _: Display = translated.into_inner();
+}
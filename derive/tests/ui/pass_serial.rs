@@ -0,0 +1,84 @@
+//! A derived I2C transformer whose extra parameter is stored, so no marker field is added.
+
+use embedded_hal::i2c::ErrorType;
+use owned_transform_derive::I2cTransformer;
+
+/// The shape of `serial::Transformer` and `serial::Owned`, without requiring a full bus.
+mod serial {
+    pub trait Transformer {
+        type AddressMode;
+        type Error;
+
+        type I2c<'a>: embedded_hal::i2c::ErrorType<Error = Self::Error>
+        where
+            Self: 'a;
+
+        fn transform<'a>(&'a mut self) -> Self::I2c<'a>;
+
+        fn into_owned(self) -> Owned<Self>
+        where
+            Self: Sized,
+        {
+            Owned(self)
+        }
+    }
+
+    pub struct Owned<T>(pub T);
+}
+
+#[derive(Debug)]
+struct BusError;
+
+impl embedded_hal::i2c::Error for BusError {
+    fn kind(&self) -> embedded_hal::i2c::ErrorKind {
+        embedded_hal::i2c::ErrorKind::Other
+    }
+}
+
+struct Bus;
+
+impl ErrorType for Bus {
+    type Error = BusError;
+}
+
+#[derive(I2cTransformer)]
+#[transformer(
+    args(handler: F),
+    bounds(F: FnMut(&mut T) -> Result<(), <T as ErrorType>::Error> + Clone),
+    ext = OwnedBusExt
+)]
+pub struct Handler<'a, T, F> {
+    parent: &'a mut T,
+    handler: F,
+}
+
+impl<'a, T, F> Handler<'a, T, F> {
+    pub fn new(parent: &'a mut T, handler: F) -> Self {
+        Self { parent, handler }
+    }
+
+    pub fn handle(&mut self) -> Result<(), T::Error>
+    where
+        T: ErrorType,
+        F: FnMut(&mut T) -> Result<(), T::Error>,
+    {
+        (self.handler)(self.parent)
+    }
+}
+
+impl<T, F> ErrorType for Handler<'_, T, F>
+where
+    T: ErrorType,
+{
+    type Error = T::Error;
+}
+
+fn main() {
+    use serial::Transformer;
+
+    let mut owned = Bus.owned_handler(|_: &mut Bus| Ok(())).0;
+    owned.transform().handle().unwrap();
+
+    // The handler is the only field after the parent
+    let HandlerT(_bus, _handler) = owned;
+}
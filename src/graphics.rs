@@ -1,19 +1,27 @@
-use core::cmp::{max, min};
+use core::cmp::max;
 use core::convert::Infallible;
 use core::fmt::Debug;
 use core::marker::PhantomData;
+use core::ops::Range;
 
 // use log::trace;
 
 use embedded_graphics::draw_target::{
     Clipped, ColorConverted, Cropped, DrawTarget, DrawTargetExt, Translated,
 };
+use embedded_graphics::pixelcolor::{
+    Bgr555, Bgr565, Bgr666, Bgr888, BinaryColor, Gray2, Gray4, Gray8, GrayColor, Rgb555, Rgb565,
+    Rgb666, Rgb888, RgbColor,
+};
 use embedded_graphics::prelude::{
-    Dimensions, IntoStorage, OriginDimensions, PixelColor, Point, RawData, Size,
+    Dimensions, OriginDimensions, PixelColor, Point, RawData, Size, Transform,
 };
 use embedded_graphics::primitives::{PointsIter, Rectangle};
 use embedded_graphics::Pixel;
 
+#[cfg(feature = "derive")]
+pub use owned_transform_derive::DrawTargetTransformer as Transformer;
+
 //
 // Owned
 //
@@ -28,6 +36,12 @@ pub trait Transformer {
 
     fn transform<'a>(&'a mut self) -> Self::DrawTarget<'a>;
 
+    /// The bounding box of [`transform`](Self::transform)'s draw target, computed from `&self`.
+    /// `None` if it can't be known without transforming.
+    fn bounding_box(&self) -> Option<Rectangle> {
+        None
+    }
+
     // fn source<'a>(&'a mut self) -> <Self as Transformer>::DrawTarget<'a>
     // where
     //     Self: Sized,
@@ -57,6 +71,22 @@ where
     fn transform<'a>(&'a mut self) -> Self::DrawTarget<'a> {
         self.0.translated(self.1)
     }
+
+    fn bounding_box(&self) -> Option<Rectangle> {
+        Some(self.0.bounding_box().translate(-self.1))
+    }
+}
+
+inner_accessors!(TranslatedT<T>);
+
+impl<T> TranslatedT<T> {
+    pub fn offset(&self) -> Point {
+        self.1
+    }
+
+    pub fn set_offset(&mut self, offset: Point) {
+        self.1 = offset;
+    }
 }
 
 pub struct CroppedT<T>(T, Rectangle);
@@ -73,6 +103,24 @@ where
     fn transform<'a>(&'a mut self) -> Self::DrawTarget<'a> {
         self.0.cropped(&self.1)
     }
+
+    fn bounding_box(&self) -> Option<Rectangle> {
+        let area = self.1.intersection(&self.0.bounding_box());
+
+        Some(Rectangle::new(Point::zero(), area.size))
+    }
+}
+
+inner_accessors!(CroppedT<T>);
+
+impl<T> CroppedT<T> {
+    pub fn area(&self) -> Rectangle {
+        self.1
+    }
+
+    pub fn set_area(&mut self, area: &Rectangle) {
+        self.1 = *area;
+    }
 }
 
 pub struct ClippedT<T>(T, Rectangle);
@@ -89,6 +137,22 @@ where
     fn transform<'a>(&'a mut self) -> Self::DrawTarget<'a> {
         self.0.clipped(&self.1)
     }
+
+    fn bounding_box(&self) -> Option<Rectangle> {
+        Some(self.1.intersection(&self.0.bounding_box()))
+    }
+}
+
+inner_accessors!(ClippedT<T>);
+
+impl<T> ClippedT<T> {
+    pub fn area(&self) -> Rectangle {
+        self.1
+    }
+
+    pub fn set_area(&mut self, area: &Rectangle) {
+        self.1 = *area;
+    }
 }
 
 pub struct ColorConvertedT<T, C>(T, PhantomData<C>);
@@ -106,8 +170,14 @@ where
     fn transform<'a>(&'a mut self) -> Self::DrawTarget<'a> {
         self.0.color_converted()
     }
+
+    fn bounding_box(&self) -> Option<Rectangle> {
+        Some(self.0.bounding_box())
+    }
 }
 
+inner_accessors!(ColorConvertedT<T, C>);
+
 pub struct RotatedT<T>(T, RotateAngle);
 
 impl<T> Transformer for RotatedT<T>
@@ -122,9 +192,63 @@ where
     fn transform<'a>(&'a mut self) -> Self::DrawTarget<'a> {
         self.0.rotated(self.1)
     }
+
+    fn bounding_box(&self) -> Option<Rectangle> {
+        let size = Orientation::from(self.1).transform_size(self.0.bounding_box().size);
+
+        Some(Rectangle::new(Point::zero(), size))
+    }
+}
+
+inner_accessors!(RotatedT<T>);
+
+impl<T> RotatedT<T> {
+    pub fn angle(&self) -> RotateAngle {
+        self.1
+    }
+
+    pub fn set_angle(&mut self, angle: RotateAngle) {
+        self.1 = angle;
+    }
+}
+
+pub struct OrientedT<T>(T, Orientation);
+
+impl<T> Transformer for OrientedT<T>
+where
+    T: DrawTarget,
+{
+    type Color = T::Color;
+    type Error = T::Error;
+
+    type DrawTarget<'a> = Oriented<'a, T> where Self: 'a;
+
+    fn transform<'a>(&'a mut self) -> Self::DrawTarget<'a> {
+        self.0.oriented(self.1)
+    }
+
+    fn bounding_box(&self) -> Option<Rectangle> {
+        let size = self.1.transform_size(self.0.bounding_box().size);
+
+        Some(Rectangle::new(Point::zero(), size))
+    }
+}
+
+inner_accessors!(OrientedT<T>);
+
+impl<T> OrientedT<T> {
+    pub fn orientation(&self) -> Orientation {
+        self.1
+    }
+
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        self.1 = orientation;
+    }
 }
 
-pub struct ScaledT<T>(T, Size);
+pub struct ScaledT<T>(T, Size, ScaleMode<T::Color>)
+where
+    T: DrawTarget;
 
 impl<T> Transformer for ScaledT<T>
 where
@@ -136,7 +260,71 @@ where
     type DrawTarget<'a> = Scaled<'a, T> where Self: 'a;
 
     fn transform<'a>(&'a mut self) -> Self::DrawTarget<'a> {
-        self.0.scaled(self.1)
+        self.0.scaled_with_mode(self.1, self.2)
+    }
+
+    fn bounding_box(&self) -> Option<Rectangle> {
+        Some(Rectangle::new(Point::zero(), self.1))
+    }
+}
+
+inner_accessors!(ScaledT<T> where T: DrawTarget);
+
+impl<T> ScaledT<T>
+where
+    T: DrawTarget,
+{
+    pub fn size(&self) -> Size {
+        self.1
+    }
+
+    pub fn set_size(&mut self, size: Size) {
+        self.1 = size;
+    }
+
+    pub fn mode(&self) -> ScaleMode<T::Color> {
+        self.2
+    }
+
+    pub fn set_mode(&mut self, mode: ScaleMode<T::Color>) {
+        self.2 = mode;
+    }
+}
+
+pub struct AffineT<T>(T, AffineMatrix);
+
+impl<T> Transformer for AffineT<T>
+where
+    T: DrawTarget,
+{
+    type Color = T::Color;
+    type Error = T::Error;
+
+    type DrawTarget<'a> = Affine<'a, T> where Self: 'a;
+
+    fn transform<'a>(&'a mut self) -> Self::DrawTarget<'a> {
+        self.0.affine(self.1)
+    }
+
+    fn bounding_box(&self) -> Option<Rectangle> {
+        let pdim = self.0.bounding_box();
+
+        Some(
+            self.1
+                .inverse_transform_rect(&Rectangle::new(Point::zero(), pdim.size)),
+        )
+    }
+}
+
+inner_accessors!(AffineT<T>);
+
+impl<T> AffineT<T> {
+    pub fn matrix(&self) -> AffineMatrix {
+        self.1
+    }
+
+    pub fn set_matrix(&mut self, matrix: AffineMatrix) {
+        self.1 = matrix;
     }
 }
 
@@ -155,18 +343,140 @@ where
     fn transform<'a>(&'a mut self) -> Self::DrawTarget<'a> {
         self.0.flushing(self.1.clone())
     }
+
+    fn bounding_box(&self) -> Option<Rectangle> {
+        Some(self.0.bounding_box())
+    }
+}
+
+inner_accessors!(FlushingT<T, F>);
+
+impl<T, F> FlushingT<T, F> {
+    pub fn flusher(&self) -> &F {
+        &self.1
+    }
+
+    pub fn set_flusher(&mut self, flusher: F) {
+        self.1 = flusher;
+    }
+}
+
+pub struct TeeT<A, B>(A, B);
+
+impl<A, B> Transformer for TeeT<A, B>
+where
+    A: DrawTarget,
+    B: DrawTarget<Color = A::Color>,
+{
+    type Color = A::Color;
+    type Error = TeeError<A::Error, B::Error>;
+
+    type DrawTarget<'a> = Tee<'a, A, B> where Self: 'a;
+
+    fn transform<'a>(&'a mut self) -> Self::DrawTarget<'a> {
+        self.0.tee(&mut self.1)
+    }
+
+    fn bounding_box(&self) -> Option<Rectangle> {
+        Some(self.0.bounding_box())
+    }
+}
+
+impl<A, B> TeeT<A, B> {
+    pub fn first(&self) -> &A {
+        &self.0
+    }
+
+    pub fn first_mut(&mut self) -> &mut A {
+        &mut self.0
+    }
+
+    pub fn second(&self) -> &B {
+        &self.1
+    }
+
+    pub fn second_mut(&mut self) -> &mut B {
+        &mut self.1
+    }
+
+    pub fn into_inner(self) -> (A, B) {
+        (self.0, self.1)
+    }
+}
+
+/// How [`Owned`] answers [`Dimensions::bounding_box`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BoundsMode {
+    /// The bounding box is computed once and reused until [`Owned::invalidate_bounds`] is called
+    /// or the transformer is reconfigured through [`Owned::inner_mut`].
+    #[default]
+    Cached,
+    /// Every call asks [`Transformer::bounding_box`], for targets that can change size.
+    /// Transformers without that hook report the bounds as of the last invalidation.
+    Live,
 }
 
-pub struct Owned<T>(T, Rectangle);
+/// The fields are the transformer, its last known bounding box, the [`BoundsMode`] and whether
+/// the transformer was handed out mutably since the bounding box was computed.
+pub struct Owned<T>(T, Rectangle, BoundsMode, bool);
 
 impl<T> Owned<T>
 where
     T: Transformer,
 {
     fn new(mut transformer: T) -> Self {
-        let bbox = transformer.transform().bounding_box();
+        let bbox = Self::compute_bounds(&mut transformer);
+
+        Self(transformer, bbox, BoundsMode::Cached, false)
+    }
+
+    pub fn with_bounds_mode(mut self, mode: BoundsMode) -> Self {
+        self.set_bounds_mode(mode);
+        self
+    }
+
+    pub fn bounds_mode(&self) -> BoundsMode {
+        self.2
+    }
+
+    pub fn set_bounds_mode(&mut self, mode: BoundsMode) {
+        self.2 = mode;
+        self.invalidate_bounds();
+    }
+
+    /// Recomputes the cached bounding box.
+    pub fn invalidate_bounds(&mut self) {
+        self.1 = Self::compute_bounds(&mut self.0);
+        self.3 = false;
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.0
+    }
+
+    /// The transformer may be reconfigured, so the bounding box is recomputed the next time it's
+    /// needed.
+    pub fn inner_mut(&mut self) -> &mut T {
+        self.3 = true;
+        &mut self.0
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+
+    fn transform(&mut self) -> T::DrawTarget<'_> {
+        if self.3 {
+            self.invalidate_bounds();
+        }
+
+        self.0.transform()
+    }
 
-        Self(transformer, bbox)
+    /// Only transforms if the transformer can't tell its bounding box by itself.
+    fn compute_bounds(transformer: &mut T) -> Rectangle {
+        Transformer::bounding_box(transformer)
+            .unwrap_or_else(|| transformer.transform().bounding_box())
     }
 }
 
@@ -181,22 +491,22 @@ where
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        self.0.transform().draw_iter(pixels)
+        self.transform().draw_iter(pixels)
     }
 
     fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
     where
         I: IntoIterator<Item = Self::Color>,
     {
-        self.0.transform().fill_contiguous(area, colors)
+        self.transform().fill_contiguous(area, colors)
     }
 
     fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
-        self.0.transform().fill_solid(area, color)
+        self.transform().fill_solid(area, color)
     }
 
     fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
-        self.0.transform().clear(color)
+        self.transform().clear(color)
     }
 }
 
@@ -205,7 +515,10 @@ where
     T: Transformer,
 {
     fn bounding_box(&self) -> Rectangle {
-        self.1
+        match self.2 {
+            BoundsMode::Cached if !self.3 => self.1,
+            _ => Transformer::bounding_box(&self.0).unwrap_or(self.1),
+        }
     }
 }
 
@@ -215,7 +528,84 @@ where
     for<'a> T::DrawTarget<'a>: Flushable,
 {
     fn flush(&mut self) -> Result<(), Self::Error> {
-        self.0.transform().flush()
+        self.transform().flush()
+    }
+}
+
+/// A [`Transformer`] whose draw target can be read back through a shared reference, which makes
+/// its [`Owned`] wrapper a [`ReadableTarget`].
+pub trait ReadableTransformer: Transformer {
+    fn get_pixel(&self, point: Point) -> Option<Self::Color>;
+}
+
+impl<T> ReadableTarget for Owned<T>
+where
+    T: ReadableTransformer,
+{
+    fn get_pixel(&self, point: Point) -> Option<Self::Color> {
+        self.inner().get_pixel(point)
+    }
+}
+
+impl<T> ReadableTransformer for TranslatedT<T>
+where
+    T: ReadableTarget,
+{
+    fn get_pixel(&self, point: Point) -> Option<Self::Color> {
+        self.0.get_pixel(point + self.1)
+    }
+}
+
+impl<T> ReadableTransformer for CroppedT<T>
+where
+    T: ReadableTarget,
+{
+    fn get_pixel(&self, point: Point) -> Option<Self::Color> {
+        let area = self.1.intersection(&self.0.bounding_box());
+
+        if !Rectangle::new(Point::zero(), area.size).contains(point) {
+            return None;
+        }
+
+        self.0.get_pixel(point + area.top_left)
+    }
+}
+
+/// Clipping only restricts drawing, the whole parent can be read.
+impl<T> ReadableTransformer for ClippedT<T>
+where
+    T: ReadableTarget,
+{
+    fn get_pixel(&self, point: Point) -> Option<Self::Color> {
+        self.0.get_pixel(point)
+    }
+}
+
+impl<T> ReadableTransformer for OrientedT<T>
+where
+    T: ReadableTarget,
+{
+    fn get_pixel(&self, point: Point) -> Option<Self::Color> {
+        oriented_pixel(&self.0, self.1, point)
+    }
+}
+
+impl<T> ReadableTransformer for RotatedT<T>
+where
+    T: ReadableTarget,
+{
+    fn get_pixel(&self, point: Point) -> Option<Self::Color> {
+        oriented_pixel(&self.0, self.1.into(), point)
+    }
+}
+
+impl<T, F> ReadableTransformer for FlushingT<T, F>
+where
+    T: ReadableTarget + 'static,
+    F: FnMut(&mut T) -> Result<(), T::Error> + Send + Clone + 'static,
+{
+    fn get_pixel(&self, point: Point) -> Option<Self::Color> {
+        self.0.get_pixel(point)
     }
 }
 
@@ -227,6 +617,9 @@ pub trait Flushable: DrawTarget {
     fn flush(&mut self) -> Result<(), Self::Error>;
 }
 
+/// The flusher of [`Flushing::noop`], which does nothing.
+pub type NoopFlusher<T> = fn(&mut T) -> Result<(), <T as DrawTarget>::Error>;
+
 pub struct Flushing<'a, T, F> {
     parent: &'a mut T,
     flusher: F,
@@ -238,7 +631,7 @@ impl<'a, T, F> Flushing<'a, T, F> {
     }
 }
 
-impl<'a, T> Flushing<'a, T, fn(&mut T) -> Result<(), T::Error>>
+impl<'a, T> Flushing<'a, T, NoopFlusher<T>>
 where
     T: DrawTarget,
 {
@@ -301,74 +694,3326 @@ where
     }
 }
 
+impl<'a, T, F> ReadableTarget for Flushing<'a, T, F>
+where
+    T: ReadableTarget,
+{
+    fn get_pixel(&self, point: Point) -> Option<Self::Color> {
+        self.parent.get_pixel(point)
+    }
+
+    fn read_rect(&self, area: &Rectangle, buf: &mut [Self::Color]) {
+        self.parent.read_rect(area, buf)
+    }
+}
+
 //
-// Buffered
+// ReadableTarget
 //
 
-pub struct Buffered<'a, T>
-where
-    T: DrawTarget,
-{
-    current: PackedFramebuffer<'a, T::Color>,
-    reference: PackedFramebuffer<'a, T::Color>,
-    target: T,
+/// A draw target whose contents can be read back, e.g. for screenshots, blending or
+/// hit-testing.
+pub trait ReadableTarget: DrawTarget {
+    /// The colour at `point`, `None` outside the bounding box.
+    fn get_pixel(&self, point: Point) -> Option<Self::Color>;
+
+    /// Reads `area` row by row into `buf`, stopping when either runs out. Entries for points
+    /// outside the bounding box are left untouched.
+    fn read_rect(&self, area: &Rectangle, buf: &mut [Self::Color]) {
+        for (point, color) in area.points().zip(buf) {
+            if let Some(pixel) = self.get_pixel(point) {
+                *color = pixel;
+            }
+        }
+    }
+
+    fn row(&self, y: i32) -> Row<'_, Self>
+    where
+        Self: Sized,
+    {
+        let bbox = self.bounding_box();
+
+        Row {
+            target: self,
+            y,
+            x: bbox.top_left.x..bbox.top_left.x + bbox.size.width as i32,
+        }
+    }
+
+    fn rows(&self) -> Rows<'_, Self>
+    where
+        Self: Sized,
+    {
+        let bbox = self.bounding_box();
+
+        Rows {
+            target: self,
+            y: bbox.top_left.y..bbox.top_left.y + bbox.size.height as i32,
+        }
+    }
 }
 
-pub const fn buffer_size<C>(display_size: Size) -> usize
-where
-    C: PixelColor + IntoStorage<Storage = u8> + From<u8>,
-{
-    PackedFramebuffer::<C>::buffer_size(display_size)
+/// The colours of one row of a [`ReadableTarget`], left to right, `None` where the target can't
+/// be read.
+pub struct Row<'a, T> {
+    target: &'a T,
+    y: i32,
+    x: Range<i32>,
 }
 
-impl<'a, T> Buffered<'a, T>
+impl<'a, T> Iterator for Row<'a, T>
 where
-    T: DrawTarget,
-    T::Color: PixelColor + IntoStorage<Storage = u8> + From<u8>,
+    T: ReadableTarget,
 {
-    pub fn new(draw_buf: &'a mut [u8], reference_buf: &'a mut [u8], display: T) -> Self {
-        let bbox = display.bounding_box();
+    type Item = Option<T::Color>;
 
-        Self {
-            current: PackedFramebuffer::<T::Color>::new(
-                draw_buf,
-                bbox.size.width as _,
-                bbox.size.height as _,
-            ),
-            reference: PackedFramebuffer::<T::Color>::new(
-                reference_buf,
-                bbox.size.width as _,
-                bbox.size.height as _,
-            ),
-            target: display,
-        }
+    fn next(&mut self) -> Option<Self::Item> {
+        let y = self.y;
+
+        self.x
+            .next()
+            .map(|x| self.target.get_pixel(Point::new(x, y)))
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.x.size_hint()
+    }
+}
+
+/// The rows of a [`ReadableTarget`], top to bottom.
+pub struct Rows<'a, T> {
+    target: &'a T,
+    y: Range<i32>,
 }
 
-impl<'a, T> Dimensions for Buffered<'a, T>
+impl<'a, T> Iterator for Rows<'a, T>
 where
-    T: DrawTarget,
-    T::Color: PixelColor + IntoStorage<Storage = u8> + From<u8>,
+    T: ReadableTarget,
 {
-    fn bounding_box(&self) -> Rectangle {
-        self.current.bounding_box()
+    type Item = Row<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.y.next().map(|y| self.target.row(y))
     }
 }
 
-impl<'a, T> DrawTarget for Buffered<'a, T>
+//
+// DynFlushable
+//
+
+/// Object-safe counterpart of [`Flushable`], implemented for every flushable draw target so that
+/// differently typed displays can be stored behind a `Box<dyn DynFlushable<..>>`.
+pub trait DynFlushable {
+    type Color: PixelColor;
+    type Error;
+
+    fn dyn_draw_iter(&mut self, pixels: &[Pixel<Self::Color>]) -> Result<(), Self::Error>;
+
+    /// `colors` covers `area` completely, row by row.
+    fn dyn_fill_contiguous(
+        &mut self,
+        area: &Rectangle,
+        colors: &[Self::Color],
+    ) -> Result<(), Self::Error>;
+
+    fn dyn_fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error>;
+
+    fn dyn_clear(&mut self, color: Self::Color) -> Result<(), Self::Error>;
+
+    fn dyn_flush(&mut self) -> Result<(), Self::Error>;
+
+    fn dyn_bounding_box(&self) -> Rectangle;
+}
+
+impl<T> DynFlushable for T
+where
+    T: Flushable,
+{
+    type Color = T::Color;
+    type Error = T::Error;
+
+    fn dyn_draw_iter(&mut self, pixels: &[Pixel<Self::Color>]) -> Result<(), Self::Error> {
+        DrawTarget::draw_iter(self, pixels.iter().copied())
+    }
+
+    fn dyn_fill_contiguous(
+        &mut self,
+        area: &Rectangle,
+        colors: &[Self::Color],
+    ) -> Result<(), Self::Error> {
+        DrawTarget::fill_contiguous(self, area, colors.iter().copied())
+    }
+
+    fn dyn_fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        DrawTarget::fill_solid(self, area, color)
+    }
+
+    fn dyn_clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        DrawTarget::clear(self, color)
+    }
+
+    fn dyn_flush(&mut self) -> Result<(), Self::Error> {
+        Flushable::flush(self)
+    }
+
+    fn dyn_bounding_box(&self) -> Rectangle {
+        Dimensions::bounding_box(self)
+    }
+}
+
+/// The most pixels handed on at once by adapters that have to split up iterators they can only
+/// consume once, such as [`BoxedFlushable`] and [`Tee`].
+const CHUNK_LEN: usize = 64;
+
+/// Passes `items` to `f` in slices of at most [`CHUNK_LEN`], without allocating.
+fn for_each_chunk<T, E, I, F>(items: I, mut f: F) -> Result<(), E>
+where
+    T: Copy,
+    I: IntoIterator<Item = T>,
+    F: FnMut(&[T]) -> Result<(), E>,
+{
+    let mut items = items.into_iter();
+
+    let first = match items.next() {
+        Some(item) => item,
+        None => return Ok(()),
+    };
+
+    let mut chunk = [first; CHUNK_LEN];
+    let mut len = 1;
+
+    for item in items {
+        if len == CHUNK_LEN {
+            f(&chunk)?;
+            len = 0;
+        }
+
+        chunk[len] = item;
+        len += 1;
+    }
+
+    f(&chunk[..len])
+}
+
+/// Splits a contiguous fill of `area` into fills of at most [`CHUNK_LEN`] pixels: runs of whole
+/// rows for narrow areas, pieces of single rows for wide ones. Like
+/// [`DrawTarget::fill_contiguous`], surplus colours are ignored and missing ones leave the rest
+/// of the area untouched.
+fn for_each_contiguous_chunk<C, E, I, F>(area: &Rectangle, colors: I, mut f: F) -> Result<(), E>
+where
+    C: Copy,
+    I: IntoIterator<Item = C>,
+    F: FnMut(&Rectangle, &[C]) -> Result<(), E>,
+{
+    let Size { width, height } = area.size;
+    let mut colors = colors.into_iter();
+
+    let first = match colors.next() {
+        Some(color) if width > 0 && height > 0 => color,
+        _ => return Ok(()),
+    };
+
+    let mut colors = core::iter::once(first).chain(colors);
+    let mut chunk = [first; CHUNK_LEN];
+
+    let (piece_width, piece_rows) = if width as usize <= CHUNK_LEN {
+        (width, (CHUNK_LEN / width as usize) as u32)
+    } else {
+        (CHUNK_LEN as u32, 1)
+    };
+
+    let mut y = 0;
+    while y < height {
+        let rows = piece_rows.min(height - y);
+
+        let mut x = 0;
+        while x < width {
+            let piece = Rectangle::new(
+                area.top_left + Point::new(x as i32, y as i32),
+                Size::new(piece_width.min(width - x), rows),
+            );
+            let len = (piece.size.width * piece.size.height) as usize;
+
+            let read = chunk[..len]
+                .iter_mut()
+                .zip(colors.by_ref())
+                .map(|(slot, color)| *slot = color)
+                .count();
+
+            if read < len {
+                // Out of colours, fill the whole rows read so far and then the partial one
+                let (full, rest) = (
+                    read as u32 / piece.size.width,
+                    read as u32 % piece.size.width,
+                );
+                let split = (full * piece.size.width) as usize;
+
+                if full > 0 {
+                    f(
+                        &Rectangle::new(piece.top_left, Size::new(piece.size.width, full)),
+                        &chunk[..split],
+                    )?;
+                }
+                if rest > 0 {
+                    f(
+                        &Rectangle::new(
+                            piece.top_left + Point::new(0, full as i32),
+                            Size::new(rest, 1),
+                        ),
+                        &chunk[split..read],
+                    )?;
+                }
+
+                return Ok(());
+            }
+
+            f(&piece, &chunk[..len])?;
+            x += piece_width;
+        }
+
+        y += rows;
+    }
+
+    Ok(())
+}
+
+pub struct BoxedFlushable<'a, C, E>(Box<dyn DynFlushable<Color = C, Error = E> + 'a>);
+
+impl<'a, C, E> BoxedFlushable<'a, C, E>
+where
+    C: PixelColor,
+{
+    pub fn new<T>(target: T) -> Self
+    where
+        T: Flushable<Color = C, Error = E> + 'a,
+    {
+        Self(Box::new(target))
+    }
+}
+
+impl<'a, C, E> DrawTarget for BoxedFlushable<'a, C, E>
+where
+    C: PixelColor,
+{
+    type Color = C;
+    type Error = E;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        // Forward the pixels in fixed-size chunks so that no allocation is needed per call
+        for_each_chunk(pixels, |chunk| self.0.dyn_draw_iter(chunk))
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        for_each_contiguous_chunk(area, colors, |piece, colors| {
+            self.0.dyn_fill_contiguous(piece, colors)
+        })
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.0.dyn_fill_solid(area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.0.dyn_clear(color)
+    }
+}
+
+impl<'a, C, E> Dimensions for BoxedFlushable<'a, C, E>
+where
+    C: PixelColor,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.0.dyn_bounding_box()
+    }
+}
+
+impl<'a, C, E> Flushable for BoxedFlushable<'a, C, E>
+where
+    C: PixelColor,
+{
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.0.dyn_flush()
+    }
+}
+
+//
+// Buffered
+//
+
+pub struct Buffered<T, S>
+where
+    T: DrawTarget,
+{
+    current: PackedFramebuffer<T::Color, S>,
+    reference: PackedFramebuffer<T::Color, S>,
+    dirty: DirtyRegions,
+    target: T,
+}
+
+pub const fn buffer_size<C>(display_size: Size) -> usize
+where
+    C: PackedColor,
+{
+    PackedFramebuffer::<C, &mut [u8]>::buffer_size(display_size)
+}
+
+pub type OwnedBuffered<T> = Buffered<T, Vec<u8>>;
+
+impl<T, S> Buffered<T, S>
+where
+    T: DrawTarget,
+    T::Color: PackedColor,
+    S: AsRef<[u8]> + AsMut<[u8]>,
+{
+    /// # Panics
+    ///
+    /// If either buffer is smaller than [`buffer_size`] of the display, use
+    /// [`try_new`](Self::try_new) to handle that instead.
+    pub fn new(draw_buf: S, reference_buf: S, display: T) -> Self {
+        match Self::with_buffers(draw_buf, reference_buf, display) {
+            Ok(buffered) => buffered,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    pub fn try_new(
+        draw_buf: S,
+        reference_buf: S,
+        display: T,
+    ) -> Result<Self, BufferedError<T::Error>> {
+        Ok(Self::with_buffers(draw_buf, reference_buf, display)?)
+    }
+
+    fn with_buffers(draw_buf: S, reference_buf: S, display: T) -> Result<Self, BufferSizeError> {
+        let bbox = display.bounding_box();
+
+        Ok(Self {
+            current: PackedFramebuffer::new(draw_buf, bbox.size.width as _, bbox.size.height as _)?,
+            reference: PackedFramebuffer::new(
+                reference_buf,
+                bbox.size.width as _,
+                bbox.size.height as _,
+            )?,
+            dirty: DirtyRegions::new(),
+            target: display,
+        })
+    }
+
+    pub fn with_byte_order(self, byte_order: ByteOrder) -> Self {
+        Self {
+            current: self.current.with_byte_order(byte_order),
+            reference: self.reference.with_byte_order(byte_order),
+            ..self
+        }
+    }
+
+    fn mark_dirty(&mut self, area: &Rectangle) {
+        self.dirty
+            .add(area.intersection(&self.current.bounding_box()));
+    }
+}
+
+impl<T> Buffered<T, Vec<u8>>
+where
+    T: DrawTarget,
+    T::Color: PackedColor,
+{
+    pub fn new_alloc(display: T) -> Self {
+        let size = buffer_size::<T::Color>(display.bounding_box().size);
+
+        Self::new(vec![0; size], vec![0; size], display)
+    }
+}
+
+impl<T, S> Dimensions for Buffered<T, S>
+where
+    T: DrawTarget,
+    T::Color: PackedColor,
+    S: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.current.bounding_box()
+    }
+}
+
+/// Reads back what was drawn, flushed or not.
+impl<T, S> ReadableTarget for Buffered<T, S>
+where
+    T: DrawTarget,
+    T::Color: PackedColor,
+    S: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn get_pixel(&self, point: Point) -> Option<Self::Color> {
+        self.current.get_pixel(point)
+    }
+}
+
+impl<T, S> DrawTarget for Buffered<T, S>
+where
+    T: DrawTarget,
+    T::Color: PackedColor,
+    S: AsRef<[u8]> + AsMut<[u8]>,
+{
+    type Error = BufferedError<T::Error>;
+
+    type Color = T::Color;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let bbox = self.current.bounding_box();
+        let mut corners: Option<(Point, Point)> = None;
+
+        self.current
+            .draw_iter(pixels.into_iter().inspect(|Pixel(point, _)| {
+                if bbox.contains(*point) {
+                    corners = Some(match corners {
+                        Some((tl, br)) => (tl.component_min(*point), br.component_max(*point)),
+                        None => (*point, *point),
+                    });
+                }
+            }))
+            .unwrap_or_else(|err| match err {});
+
+        if let Some((top_left, bottom_right)) = corners {
+            self.mark_dirty(&Rectangle::with_corners(top_left, bottom_right));
+        }
+
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.current
+            .fill_contiguous(area, colors)
+            .unwrap_or_else(|err| match err {});
+        self.mark_dirty(area);
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.current
+            .fill_solid(area, color)
+            .unwrap_or_else(|err| match err {});
+        self.mark_dirty(area);
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.current.clear(color).unwrap_or_else(|err| match err {});
+
+        self.dirty.clear();
+        self.mark_dirty(&self.current.bounding_box());
+
+        Ok(())
+    }
+}
+
+impl<T, S> Flushable for Buffered<T, S>
+where
+    T: Flushable,
+    T::Color: PackedColor,
+    S: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        for area in self.dirty.regions() {
+            self.reference
+                .apply_area(&self.current, area, &mut self.target)
+                .map_err(BufferedError::Target)?;
+        }
+
+        self.dirty.clear();
+
+        self.target.flush().map_err(BufferedError::Target)
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct BufferSizeError {
+    pub required: usize,
+    pub actual: usize,
+}
+
+impl core::fmt::Display for BufferSizeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Framebuffer too small: {} bytes required, {} bytes provided",
+            self.required, self.actual
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BufferedError<E> {
+    BufferSize(BufferSizeError),
+    Target(E),
+}
+
+impl<E> From<BufferSizeError> for BufferedError<E> {
+    fn from(err: BufferSizeError) -> Self {
+        Self::BufferSize(err)
+    }
+}
+
+impl<E> core::fmt::Display for BufferedError<E>
+where
+    E: Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::BufferSize(err) => write!(f, "{}", err),
+            Self::Target(err) => write!(f, "Display error: {:?}", err),
+        }
+    }
+}
+
+impl std::error::Error for BufferSizeError {}
+
+impl<E> std::error::Error for BufferedError<E>
+where
+    E: Debug,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::BufferSize(err) => Some(err),
+            Self::Target(_) => None,
+        }
+    }
+}
+
+const DIRTY_REGIONS: usize = 8;
+
+struct DirtyRegions {
+    regions: [Rectangle; DIRTY_REGIONS],
+    len: usize,
+}
+
+impl DirtyRegions {
+    const fn new() -> Self {
+        Self {
+            regions: [Rectangle::new(Point::zero(), Size::zero()); DIRTY_REGIONS],
+            len: 0,
+        }
+    }
+
+    fn regions(&self) -> &[Rectangle] {
+        &self.regions[..self.len]
+    }
+
+    fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    fn add(&mut self, area: Rectangle) {
+        if area.is_zero_sized() {
+            return;
+        }
+
+        let mut area = area;
+
+        // Absorb every region which overlaps or touches the new one; the grown area might
+        // now touch regions that were already checked, so start over after each merge
+        let mut index = 0;
+        while index < self.len {
+            if !self.regions[index]
+                .intersection(&area.offset(1))
+                .is_zero_sized()
+            {
+                area = Self::union(&self.regions[index], &area);
+                self.remove(index);
+                index = 0;
+            } else {
+                index += 1;
+            }
+        }
+
+        if self.len == DIRTY_REGIONS {
+            // Out of slots: merge with the region whose union adds the fewest pixels
+            let index = (0..self.len)
+                .min_by_key(|index| {
+                    let region = &self.regions[*index];
+
+                    Self::area(&Self::union(region, &area)) - Self::area(region)
+                })
+                .unwrap();
+
+            area = Self::union(&self.regions[index], &area);
+            self.remove(index);
+        }
+
+        self.regions[self.len] = area;
+        self.len += 1;
+    }
+
+    fn remove(&mut self, index: usize) {
+        self.len -= 1;
+        self.regions[index] = self.regions[self.len];
+    }
+
+    fn union(a: &Rectangle, b: &Rectangle) -> Rectangle {
+        let a_br = a.bottom_right().unwrap_or(a.top_left);
+        let b_br = b.bottom_right().unwrap_or(b.top_left);
+
+        Rectangle::with_corners(
+            a.top_left.component_min(b.top_left),
+            a_br.component_max(b_br),
+        )
+    }
+
+    fn area(rect: &Rectangle) -> u64 {
+        rect.size.width as u64 * rect.size.height as u64
+    }
+}
+
+//
+// PackedFramebuffer
+//
+
+pub trait PackedColor: PixelColor {
+    fn to_bits(self) -> u32;
+
+    fn from_bits(bits: u32) -> Self;
+}
+
+impl<C> PackedColor for C
+where
+    C: PixelColor + From<C::Raw> + Into<C::Raw>,
+    <C::Raw as RawData>::Storage: Into<u32>,
+{
+    fn to_bits(self) -> u32 {
+        Into::<C::Raw>::into(self).into_inner().into()
+    }
+
+    fn from_bits(bits: u32) -> Self {
+        C::Raw::from_u32(bits).into()
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ByteOrder {
+    BigEndian,
+    LittleEndian,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum MemoryLayout {
+    /// Row-major, consecutive pixels of a row share a byte.
+    Horizontal,
+    /// Paged, a byte holds a column of vertically adjacent pixels (SSD1306, SH1106).
+    Vertical,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum BitOrder {
+    LsbFirst,
+    MsbFirst,
+}
+
+pub struct PackedFramebuffer<COLOR, S> {
+    buf: S,
+    width: usize,
+    height: usize,
+    layout: MemoryLayout,
+    bit_order: BitOrder,
+    byte_order: ByteOrder,
+    row_alignment: usize,
+    stride: usize,
+    _color: PhantomData<COLOR>,
+}
+
+impl<COLOR, S> PackedFramebuffer<COLOR, S>
+where
+    COLOR: PackedColor,
+    S: AsRef<[u8]> + AsMut<[u8]>,
+{
+    const BITS_PER_PIXEL: usize = Self::bits_per_pixel();
+    const BYTES_PER_PIXEL: usize = (Self::BITS_PER_PIXEL + 7) / 8;
+    const PIXEL_MASK: u8 = if Self::BITS_PER_PIXEL >= 8 {
+        0xff
+    } else {
+        ((1 << Self::BITS_PER_PIXEL) - 1) as u8
+    };
+    const PIXELS_PER_BYTE: usize = if Self::BITS_PER_PIXEL >= 8 {
+        1
+    } else {
+        8 / Self::BITS_PER_PIXEL
+    };
+    const PIXELS_PER_BYTE_SHIFT: usize = if Self::BITS_PER_PIXEL >= 8 {
+        0
+    } else {
+        Self::BITS_PER_PIXEL
+    };
+
+    pub fn new(buf: S, width: usize, height: usize) -> Result<Self, BufferSizeError> {
+        Self::with_layout(
+            buf,
+            width,
+            height,
+            MemoryLayout::Horizontal,
+            BitOrder::LsbFirst,
+        )
+    }
+
+    pub fn with_layout(
+        buf: S,
+        width: usize,
+        height: usize,
+        layout: MemoryLayout,
+        bit_order: BitOrder,
+    ) -> Result<Self, BufferSizeError> {
+        Self {
+            buf,
+            width,
+            height,
+            layout,
+            bit_order,
+            byte_order: ByteOrder::BigEndian,
+            row_alignment: 1,
+            stride: Self::stride_for(width, layout, 1),
+            _color: PhantomData,
+        }
+        .validated()
+    }
+
+    /// Pads every row (or page, for the vertical layout) to a multiple of `row_alignment` bytes.
+    pub fn with_row_alignment(self, row_alignment: usize) -> Result<Self, BufferSizeError> {
+        let row_alignment = max(row_alignment, 1);
+
+        Self {
+            row_alignment,
+            stride: Self::stride_for(self.width, self.layout, row_alignment),
+            ..self
+        }
+        .validated()
+    }
+
+    fn validated(self) -> Result<Self, BufferSizeError> {
+        let required = self.size_in_bytes();
+        let actual = self.buf.as_ref().len();
+
+        if actual < required {
+            return Err(BufferSizeError { required, actual });
+        }
+
+        Ok(self)
+    }
+
+    pub fn with_byte_order(self, byte_order: ByteOrder) -> Self {
+        Self { byte_order, ..self }
+    }
+
+    pub fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+
+    pub fn layout(&self) -> MemoryLayout {
+        self.layout
+    }
+
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
+    pub fn row_alignment(&self) -> usize {
+        self.row_alignment
+    }
+
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf.as_ref()[..self.size_in_bytes()]
+    }
+
+    pub const fn buffer_size(display_size: Size) -> usize {
+        Self::layout_buffer_size(display_size, MemoryLayout::Horizontal, 1)
+    }
+
+    pub const fn layout_buffer_size(
+        display_size: Size,
+        layout: MemoryLayout,
+        row_alignment: usize,
+    ) -> usize {
+        let stride = Self::stride_for(display_size.width as usize, layout, row_alignment);
+        let height = display_size.height as usize;
+
+        match layout {
+            MemoryLayout::Horizontal => stride * height,
+            MemoryLayout::Vertical => stride * Self::div_ceil(height, Self::PIXELS_PER_BYTE),
+        }
+    }
+
+    const fn stride_for(width: usize, layout: MemoryLayout, row_alignment: usize) -> usize {
+        let stride = match layout {
+            MemoryLayout::Horizontal => Self::div_ceil(width * Self::BITS_PER_PIXEL, 8),
+            MemoryLayout::Vertical => width * Self::BYTES_PER_PIXEL,
+        };
+
+        let row_alignment = if row_alignment == 0 { 1 } else { row_alignment };
+
+        Self::div_ceil(stride, row_alignment) * row_alignment
+    }
+
+    const fn div_ceil(value: usize, divisor: usize) -> usize {
+        (value + divisor - 1) / divisor
+    }
+
+    fn size_in_bytes(&self) -> usize {
+        Self::layout_buffer_size(
+            Size::new(self.width as _, self.height as _),
+            self.layout,
+            self.row_alignment,
+        )
+    }
+
+    const fn bits_per_pixel() -> usize {
+        match COLOR::Raw::BITS_PER_PIXEL {
+            0..=1 => 1,
+            2 => 2,
+            3..=4 => 4,
+            5..=8 => 8,
+            9..=16 => 16,
+            17..=24 => 24,
+            _ => 32,
+        }
+    }
+
+    pub fn apply<N, D>(
+        &mut self,
+        new: &PackedFramebuffer<COLOR, N>,
+        to: &mut D,
+    ) -> Result<usize, D::Error>
+    where
+        N: AsRef<[u8]> + AsMut<[u8]>,
+        D: DrawTarget<Color = COLOR>,
+    {
+        let bbox = self.bounding_box();
+
+        self.apply_area(new, &bbox, to)
+    }
+
+    pub fn apply_area<N, D>(
+        &mut self,
+        new: &PackedFramebuffer<COLOR, N>,
+        area: &Rectangle,
+        to: &mut D,
+    ) -> Result<usize, D::Error>
+    where
+        N: AsRef<[u8]> + AsMut<[u8]>,
+        D: DrawTarget<Color = COLOR>,
+    {
+        let area = area.intersection(&self.bounding_box());
+
+        let mut changes = 0_usize;
+        let mut corners: Option<(Point, Point)> = None;
+
+        for point in area.points() {
+            let (byte_offset, bits_offset) = self.pixel_offset(point);
+
+            if self.get(byte_offset, bits_offset) != new.get(byte_offset, bits_offset) {
+                changes += 1;
+
+                corners = Some(match corners {
+                    Some((tl, br)) => (tl.component_min(point), br.component_max(point)),
+                    None => (point, point),
+                });
+            }
+        }
+
+        if let Some((top_left, bottom_right)) = corners {
+            let changed = Rectangle::with_corners(top_left, bottom_right);
+
+            to.fill_contiguous(
+                &changed,
+                changed.points().map(|point| {
+                    let (byte_offset, bits_offset) = new.pixel_offset(point);
+
+                    new.get(byte_offset, bits_offset)
+                }),
+            )?;
+
+            for point in changed.points() {
+                let (byte_offset, bits_offset) = self.pixel_offset(point);
+
+                self.set(byte_offset, bits_offset, new.get(byte_offset, bits_offset));
+            }
+        }
+
+        // trace!(
+        //     "Display updated ({}/{} changed pixels)",
+        //     changes,
+        //     area.size.width * area.size.height
+        // );
+
+        Ok(changes)
+    }
+
+    fn offsets(&self, area: Rectangle) -> impl Iterator<Item = (usize, usize)> {
+        let layout = self.layout;
+        let bit_order = self.bit_order;
+        let stride = self.stride;
+
+        area.intersection(&self.bounding_box())
+            .points()
+            .map(move |point| {
+                Self::locate(
+                    layout,
+                    bit_order,
+                    stride,
+                    point.x as usize,
+                    point.y as usize,
+                )
+            })
+    }
+
+    #[inline(always)]
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline(always)]
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline(always)]
+    fn to_bits(color: COLOR) -> u32 {
+        color.to_bits()
+    }
+
+    #[inline(always)]
+    fn from_bits(bits: u32) -> COLOR {
+        COLOR::from_bits(bits)
+    }
+
+    #[inline(always)]
+    fn pixel_offset(&self, point: Point) -> (usize, usize) {
+        Self::locate(
+            self.layout,
+            self.bit_order,
+            self.stride,
+            point.x as usize,
+            point.y as usize,
+        )
+    }
+
+    #[inline(always)]
+    fn locate(
+        layout: MemoryLayout,
+        bit_order: BitOrder,
+        stride: usize,
+        x: usize,
+        y: usize,
+    ) -> (usize, usize) {
+        let (byte_offset, index) = match layout {
+            MemoryLayout::Horizontal => (
+                y * stride + x * Self::BYTES_PER_PIXEL / Self::PIXELS_PER_BYTE,
+                x % Self::PIXELS_PER_BYTE,
+            ),
+            MemoryLayout::Vertical => (
+                y / Self::PIXELS_PER_BYTE * stride + x * Self::BYTES_PER_PIXEL,
+                y % Self::PIXELS_PER_BYTE,
+            ),
+        };
+
+        let index = match bit_order {
+            BitOrder::LsbFirst => index,
+            BitOrder::MsbFirst => Self::PIXELS_PER_BYTE - 1 - index,
+        };
+
+        (byte_offset, Self::PIXELS_PER_BYTE_SHIFT * index)
+    }
+
+    #[inline(always)]
+    fn get(&self, byte_offset: usize, bits_offset: usize) -> COLOR {
+        if Self::BYTES_PER_PIXEL == 1 {
+            let byte = self.buf.as_ref()[byte_offset];
+
+            Self::from_bits(((byte >> bits_offset) & Self::PIXEL_MASK) as u32)
+        } else {
+            let bytes = &self.buf.as_ref()[byte_offset..byte_offset + Self::BYTES_PER_PIXEL];
+
+            let bits = match self.byte_order {
+                ByteOrder::BigEndian => bytes.iter().fold(0, |bits, byte| bits << 8 | *byte as u32),
+                ByteOrder::LittleEndian => bytes
+                    .iter()
+                    .rev()
+                    .fold(0, |bits, byte| bits << 8 | *byte as u32),
+            };
+
+            Self::from_bits(bits)
+        }
+    }
+
+    #[inline(always)]
+    fn set(&mut self, byte_offset: usize, bits_offset: usize, color: COLOR) {
+        let bits = Self::to_bits(color);
+
+        if Self::BYTES_PER_PIXEL == 1 {
+            let byte = &mut self.buf.as_mut()[byte_offset];
+            *byte &= !(Self::PIXEL_MASK << bits_offset);
+            *byte |= (bits as u8) << bits_offset;
+        } else {
+            let byte_order = self.byte_order;
+            let bytes = &mut self.buf.as_mut()[byte_offset..byte_offset + Self::BYTES_PER_PIXEL];
+
+            for (index, byte) in bytes.iter_mut().enumerate() {
+                let shift = match byte_order {
+                    ByteOrder::BigEndian => Self::BYTES_PER_PIXEL - 1 - index,
+                    ByteOrder::LittleEndian => index,
+                };
+
+                *byte = (bits >> (shift * 8)) as u8;
+            }
+        }
+    }
+}
+
+impl<COLOR, S> Dimensions for PackedFramebuffer<COLOR, S>
+where
+    COLOR: PackedColor,
+    S: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(
+            Point::zero(),
+            Size::new(self.width() as u32, self.height() as u32),
+        )
+    }
+}
+
+impl<COLOR, S> ReadableTarget for PackedFramebuffer<COLOR, S>
+where
+    COLOR: PackedColor,
+    S: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn get_pixel(&self, point: Point) -> Option<Self::Color> {
+        self.bounding_box().contains(point).then(|| {
+            let (byte_offset, bits_offset) = self.pixel_offset(point);
+
+            self.get(byte_offset, bits_offset)
+        })
+    }
+}
+
+impl<COLOR, S> DrawTarget for PackedFramebuffer<COLOR, S>
+where
+    COLOR: PackedColor,
+    S: AsRef<[u8]> + AsMut<[u8]>,
+{
+    type Error = Infallible;
+
+    type Color = COLOR;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for pixel in pixels {
+            if pixel.0.x >= 0
+                && pixel.0.x < self.width() as _
+                && pixel.0.y >= 0
+                && pixel.0.y < self.height() as _
+            {
+                let (byte_offset, bits_offset) = self.pixel_offset(pixel.0);
+
+                self.set(byte_offset, bits_offset, pixel.1);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let bbox = self.bounding_box();
+
+        for (point, color) in area.points().zip(colors) {
+            if bbox.contains(point) {
+                let (byte_offset, bits_offset) = self.pixel_offset(point);
+
+                self.set(byte_offset, bits_offset, color);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        for (byte_offset, bits_offset) in self.offsets(*area) {
+            self.set(byte_offset, bits_offset, color);
+        }
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        if Self::to_bits(color) == 0 {
+            for byte in self.buf.as_mut().iter_mut() {
+                *byte = 0;
+            }
+        } else {
+            for (byte_offset, bits_offset) in self.offsets(self.bounding_box()) {
+                self.set(byte_offset, bits_offset, color);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//
+// Oriented
+//
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum RotateAngle {
+    Degrees90,
+    Degrees180,
+    Degrees270,
+}
+
+/// One of the eight symmetries of a rectangle. Rotations are clockwise, and the flips and
+/// transposes are applied to the logical image before it is written to the parent target.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Orientation {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipHorizontal,
+    FlipVertical,
+    /// Mirror along the main diagonal (swap x and y).
+    Transpose,
+    /// Mirror along the anti-diagonal.
+    AntiTranspose,
+}
+
+impl Orientation {
+    pub const ALL: [Orientation; 8] = [
+        Orientation::Identity,
+        Orientation::Rotate90,
+        Orientation::Rotate180,
+        Orientation::Rotate270,
+        Orientation::FlipHorizontal,
+        Orientation::FlipVertical,
+        Orientation::Transpose,
+        Orientation::AntiTranspose,
+    ];
+
+    pub fn swaps_axes(&self) -> bool {
+        matches!(
+            self,
+            Orientation::Rotate90
+                | Orientation::Rotate270
+                | Orientation::Transpose
+                | Orientation::AntiTranspose
+        )
+    }
+
+    /// The orientation undoing this one, mapping points of the parent back onto the view.
+    pub fn inverse(&self) -> Orientation {
+        match self {
+            Orientation::Rotate90 => Orientation::Rotate270,
+            Orientation::Rotate270 => Orientation::Rotate90,
+            orientation => *orientation,
+        }
+    }
+
+    pub fn transform_size(&self, size: Size) -> Size {
+        if self.swaps_axes() {
+            Size::new(size.height, size.width)
+        } else {
+            size
+        }
+    }
+
+    /// Maps a point of the oriented view onto the parent area `pdim`.
+    pub fn transform(&self, point: Point, pdim: &Rectangle) -> Point {
+        let right = pdim.size.width as i32 - 1;
+        let bottom = pdim.size.height as i32 - 1;
+
+        let Point { x, y } = point;
+
+        let point = match self {
+            Orientation::Identity => Point::new(x, y),
+            Orientation::Rotate90 => Point::new(right - y, x),
+            Orientation::Rotate180 => Point::new(right - x, bottom - y),
+            Orientation::Rotate270 => Point::new(y, bottom - x),
+            Orientation::FlipHorizontal => Point::new(right - x, y),
+            Orientation::FlipVertical => Point::new(x, bottom - y),
+            Orientation::Transpose => Point::new(y, x),
+            Orientation::AntiTranspose => Point::new(right - y, bottom - x),
+        };
+
+        pdim.top_left + point
+    }
+
+    pub fn transform_rect(&self, rect: &Rectangle, pdim: &Rectangle) -> Rectangle {
+        match rect.bottom_right() {
+            Some(bottom_right) => {
+                let point1 = self.transform(rect.top_left, pdim);
+                let point2 = self.transform(bottom_right, pdim);
+
+                Rectangle::with_corners(point1, point2)
+            }
+            None => Rectangle::new(self.transform(rect.top_left, pdim), Size::zero()),
+        }
+    }
+}
+
+impl From<RotateAngle> for Orientation {
+    fn from(angle: RotateAngle) -> Self {
+        match angle {
+            RotateAngle::Degrees90 => Orientation::Rotate90,
+            RotateAngle::Degrees180 => Orientation::Rotate180,
+            RotateAngle::Degrees270 => Orientation::Rotate270,
+        }
+    }
+}
+
+/// `None` leaves the target as is.
+impl From<Option<RotateAngle>> for Orientation {
+    fn from(angle: Option<RotateAngle>) -> Self {
+        angle.map_or(Orientation::Identity, Orientation::from)
+    }
+}
+
+/// Runtime reorientation, e.g. switching between portrait and landscape, without changing the
+/// type of the owned display.
+impl<T> Owned<OrientedT<T>>
+where
+    T: DrawTarget,
+{
+    pub fn orientation(&self) -> Orientation {
+        self.inner().orientation()
+    }
+
+    /// Changes the orientation, the reported bounding box follows. What was drawn before is
+    /// left on the target.
+    pub fn set_orientation(&mut self, orientation: impl Into<Orientation>) {
+        self.inner_mut().set_orientation(orientation.into());
+    }
+
+    /// Like [`set_orientation`](Self::set_orientation), then clears the whole target.
+    pub fn set_orientation_and_clear(
+        &mut self,
+        orientation: impl Into<Orientation>,
+        color: T::Color,
+    ) -> Result<(), T::Error> {
+        self.set_orientation(orientation);
+        self.clear(color)
+    }
+}
+
+pub type Rotated<'a, T> = Oriented<'a, T>;
+
+pub struct Oriented<'a, T>
+where
+    T: DrawTarget,
+{
+    parent: &'a mut T,
+    orientation: Orientation,
+}
+
+impl<'a, T> Oriented<'a, T>
+where
+    T: DrawTarget,
+{
+    pub fn new(parent: &'a mut T, orientation: impl Into<Orientation>) -> Self {
+        Self {
+            parent,
+            orientation: orientation.into(),
+        }
+    }
+}
+
+impl<'a, T> DrawTarget for Oriented<'a, T>
+where
+    T: DrawTarget,
+{
+    type Error = T::Error;
+    type Color = T::Color;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let pdim = self.parent.bounding_box();
+        let orientation = self.orientation;
+
+        self.parent.draw_iter(
+            pixels
+                .into_iter()
+                .map(|pixel| Pixel(orientation.transform(pixel.0, &pdim), pixel.1)),
+        )
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let pdim = self.parent.bounding_box();
+        let orientation = self.orientation;
+
+        if orientation == Orientation::Identity {
+            return self.parent.fill_contiguous(
+                &Rectangle::new(pdim.top_left + area.top_left, area.size),
+                colors,
+            );
+        }
+
+        self.parent.draw_iter(
+            area.points()
+                .zip(colors)
+                .map(|(pos, color)| Pixel(orientation.transform(pos, &pdim), color)),
+        )
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let pdim = self.parent.bounding_box();
+
+        let area = area.intersection(&self.bounding_box());
+        if area.is_zero_sized() {
+            return Ok(());
+        }
+
+        self.parent
+            .fill_solid(&self.orientation.transform_rect(&area, &pdim), color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        // Every orientation covers the whole parent area
+        self.parent.clear(color)
+    }
+}
+
+impl<'a, T> OriginDimensions for Oriented<'a, T>
+where
+    T: DrawTarget,
+{
+    fn size(&self) -> Size {
+        let bbox = self.parent.bounding_box();
+
+        self.orientation.transform_size(bbox.size)
+    }
+}
+
+impl<'a, T> ReadableTarget for Oriented<'a, T>
+where
+    T: ReadableTarget,
+{
+    fn get_pixel(&self, point: Point) -> Option<Self::Color> {
+        oriented_pixel(self.parent, self.orientation, point)
+    }
+}
+
+/// Reads `point` of `parent` as seen through `orientation`.
+fn oriented_pixel<T>(parent: &T, orientation: Orientation, point: Point) -> Option<T::Color>
+where
+    T: ReadableTarget,
+{
+    let pdim = parent.bounding_box();
+
+    if !Rectangle::new(Point::zero(), orientation.transform_size(pdim.size)).contains(point) {
+        return None;
+    }
+
+    parent.get_pixel(orientation.transform(point, &pdim))
+}
+
+//
+// Scaled
+//
+
+pub struct BoxFilter<C> {
+    luma: fn(C) -> u32,
+    from_luma: fn(u32) -> C,
+}
+
+impl<C> BoxFilter<C>
+where
+    C: GrayColor + PackedColor,
+{
+    pub fn new() -> Self {
+        Self {
+            luma: |color: C| color.luma() as u32,
+            from_luma: C::from_bits,
+        }
+    }
+}
+
+impl<C> Default for BoxFilter<C>
+where
+    C: GrayColor + PackedColor,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> Clone for BoxFilter<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C> Copy for BoxFilter<C> {}
+
+pub enum ScaleMode<C> {
+    /// Every logical pixel is drawn as the block of parent pixels it covers, contiguous fills are
+    /// mapped onto a single parent fill. When downscaling, each parent pixel keeps exactly one of
+    /// the logical pixels falling into it.
+    Nearest,
+    /// Like `Nearest`, but images drawn with `fill_contiguous` are downscaled by averaging the
+    /// luma of all the logical pixels covered by a parent pixel.
+    Box(BoxFilter<C>),
+}
+
+impl<C> ScaleMode<C>
+where
+    C: GrayColor + PackedColor,
+{
+    pub fn box_filter() -> Self {
+        Self::Box(BoxFilter::new())
+    }
+}
+
+impl<C> Clone for ScaleMode<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C> Copy for ScaleMode<C> {}
+
+pub struct Scaled<'a, T>
+where
+    T: DrawTarget,
+{
+    parent: &'a mut T,
+    size: Size,
+    mode: ScaleMode<T::Color>,
+}
+
+impl<'a, T> Scaled<'a, T>
+where
+    T: DrawTarget,
+{
+    pub fn new(parent: &'a mut T, size: Size) -> Self {
+        Self::with_mode(parent, size, ScaleMode::Nearest)
+    }
+
+    pub fn with_mode(parent: &'a mut T, size: Size, mode: ScaleMode<T::Color>) -> Self {
+        Self { parent, size, mode }
+    }
+
+    /// First parent coordinate covered by the logical coordinate `value`.
+    fn scale(value: i32, parent: u32, logical: u32) -> i32 {
+        if logical == 0 {
+            return 0;
+        }
+
+        (value as i64 * parent as i64).div_euclid(logical as i64) as i32
+    }
+
+    /// First logical coordinate covering the parent coordinate `value`.
+    fn unscale(value: i32, parent: u32, logical: u32) -> i32 {
+        if parent == 0 {
+            return 0;
+        }
+
+        -(-(value as i64) * logical as i64).div_euclid(parent as i64) as i32
+    }
+
+    fn transform_rect(area: &Rectangle, size: Size, pdim: &Rectangle) -> Rectangle {
+        let x0 = Self::scale(area.top_left.x, pdim.size.width, size.width);
+        let y0 = Self::scale(area.top_left.y, pdim.size.height, size.height);
+
+        let x1 = Self::scale(
+            area.top_left.x + area.size.width as i32,
+            pdim.size.width,
+            size.width,
+        );
+        let y1 = Self::scale(
+            area.top_left.y + area.size.height as i32,
+            pdim.size.height,
+            size.height,
+        );
+
+        Rectangle::new(
+            pdim.top_left + Point::new(x0, y0),
+            Size::new((x1 - x0) as u32, (y1 - y0) as u32),
+        )
+    }
+
+    fn transform_pixel(point: Point, size: Size, pdim: &Rectangle) -> Rectangle {
+        Self::transform_rect(&Rectangle::new(point, Size::new(1, 1)), size, pdim)
+    }
+
+    /// Whether every logical pixel maps to at most one parent pixel.
+    fn is_downscale(size: Size, pdim: &Rectangle) -> bool {
+        size.width >= pdim.size.width && size.height >= pdim.size.height
+    }
+}
+
+impl<'a, T> DrawTarget for Scaled<'a, T>
+where
+    T: DrawTarget,
+{
+    type Error = T::Error;
+    type Color = T::Color;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let pdim = self.parent.bounding_box();
+        let size = self.size;
+
+        if Self::is_downscale(size, &pdim) {
+            self.parent
+                .draw_iter(pixels.into_iter().filter_map(|Pixel(point, color)| {
+                    let block = Self::transform_pixel(point, size, &pdim);
+
+                    (!block.is_zero_sized()).then_some(Pixel(block.top_left, color))
+                }))
+        } else {
+            for Pixel(point, color) in pixels {
+                let block = Self::transform_pixel(point, size, &pdim);
+
+                if !block.is_zero_sized() {
+                    self.parent.fill_solid(&block, color)?;
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let pdim = self.parent.bounding_box();
+        let size = self.size;
+
+        let target = Self::transform_rect(area, size, &pdim);
+
+        if !Self::is_downscale(size, &pdim) {
+            let len = area.size.width as usize * area.size.height as usize;
+            let colors: Vec<_> = colors.into_iter().take(len).collect();
+
+            if colors.len() < len {
+                return self.draw_iter(
+                    area.points()
+                        .zip(colors)
+                        .map(|(point, color)| Pixel(point, color)),
+                );
+            }
+
+            // Every parent pixel takes the colour of the last logical pixel covering it
+            let covering = |value: i32, start: i32, parent: u32, logical: u32| {
+                (Self::unscale(value + 1, parent, logical) - 1 - start) as usize
+            };
+
+            return self.parent.fill_contiguous(
+                &target,
+                target.points().map(|point| {
+                    let point = point - pdim.top_left;
+                    let x = covering(point.x, area.top_left.x, pdim.size.width, size.width);
+                    let y = covering(point.y, area.top_left.y, pdim.size.height, size.height);
+
+                    colors[y * area.size.width as usize + x]
+                }),
+            );
+        }
+
+        if let ScaleMode::Box(filter) = self.mode {
+            let lumas = colors.into_iter().map(filter.luma).collect::<Vec<_>>();
+            let stride = area.size.width as i32;
+
+            let range = |value: i32, start: i32, len: u32, parent: u32, logical: u32| {
+                let from = Self::unscale(value, parent, logical);
+                let to = max(Self::unscale(value + 1, parent, logical), from + 1);
+
+                (
+                    from.clamp(start, start + len as i32),
+                    to.clamp(start, start + len as i32),
+                )
+            };
+
+            return self.parent.fill_contiguous(
+                &target,
+                target.points().map_while(|point| {
+                    let point = point - pdim.top_left;
+
+                    let (x0, x1) = range(
+                        point.x,
+                        area.top_left.x,
+                        area.size.width,
+                        pdim.size.width,
+                        size.width,
+                    );
+                    let (y0, y1) = range(
+                        point.y,
+                        area.top_left.y,
+                        area.size.height,
+                        pdim.size.height,
+                        size.height,
+                    );
+
+                    let (sum, count) = (y0..y1)
+                        .flat_map(|y| (x0..x1).map(move |x| (x, y)))
+                        .filter_map(|(x, y)| {
+                            let index = (y - area.top_left.y) * stride + (x - area.top_left.x);
+
+                            lumas.get(index as usize)
+                        })
+                        .fold((0, 0), |(sum, count), luma| (sum + luma, count + 1));
+
+                    (count > 0).then(|| (filter.from_luma)((sum + count / 2) / count))
+                }),
+            );
+        }
+
+        self.parent.fill_contiguous(
+            &target,
+            area.points()
+                .zip(colors)
+                .filter(|(point, _)| !Self::transform_pixel(*point, size, &pdim).is_zero_sized())
+                .map(|(_, color)| color),
+        )
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let pdim = self.parent.bounding_box();
+        let area = Self::transform_rect(area, self.size, &pdim);
+
+        self.parent.fill_solid(&area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.parent.clear(color)
+    }
+}
+
+impl<'a, T> OriginDimensions for Scaled<'a, T>
+where
+    T: DrawTarget,
+{
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+//
+// Affine
+//
+
+/// A 2x3 matrix in 16.16 fixed point mapping logical coordinates onto the parent target:
+/// `x' = a * x + b * y + tx` and `y' = c * x + d * y + ty`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct AffineMatrix {
+    pub a: i32,
+    pub b: i32,
+    pub c: i32,
+    pub d: i32,
+    pub tx: i32,
+    pub ty: i32,
+}
+
+impl AffineMatrix {
+    pub const FRAC_BITS: u32 = 16;
+    pub const ONE: i32 = 1 << Self::FRAC_BITS;
+
+    pub const IDENTITY: Self = Self::new(Self::ONE, 0, 0, Self::ONE, 0, 0);
+
+    pub const fn new(a: i32, b: i32, c: i32, d: i32, tx: i32, ty: i32) -> Self {
+        Self { a, b, c, d, tx, ty }
+    }
+
+    /// Converts `numerator / denominator` to fixed point.
+    pub const fn fixed(numerator: i32, denominator: i32) -> i32 {
+        ((numerator as i64 * Self::ONE as i64) / denominator as i64) as i32
+    }
+
+    /// Largest translation representable in the 16.16 fixed point fields, in whole pixels.
+    pub const MAX_TRANSLATION: i32 = i32::MAX >> Self::FRAC_BITS;
+
+    /// # Panics
+    ///
+    /// Panics if either component of `offset` is outside `-MAX_TRANSLATION - 1..=MAX_TRANSLATION`,
+    /// see [`checked_translation`](Self::checked_translation).
+    pub const fn translation(offset: Point) -> Self {
+        match Self::checked_translation(offset) {
+            Some(matrix) => matrix,
+            None => panic!("translation out of the fixed point range"),
+        }
+    }
+
+    /// Like [`translation`](Self::translation), but returns `None` when the offset doesn't fit.
+    pub const fn checked_translation(offset: Point) -> Option<Self> {
+        match (
+            offset.x.checked_mul(Self::ONE),
+            offset.y.checked_mul(Self::ONE),
+        ) {
+            (Some(tx), Some(ty)) => Some(Self::new(Self::ONE, 0, 0, Self::ONE, tx, ty)),
+            _ => None,
+        }
+    }
+
+    pub const fn scaling(sx: i32, sy: i32) -> Self {
+        Self::new(sx, 0, 0, sy, 0, 0)
+    }
+
+    pub const fn shearing(shx: i32, shy: i32) -> Self {
+        Self::new(Self::ONE, shx, shy, Self::ONE, 0, 0)
+    }
+
+    /// Clockwise rotation (on screen) around the origin, given the fixed point cosine and sine
+    /// of the angle.
+    pub const fn rotation(cos: i32, sin: i32) -> Self {
+        Self::new(cos, -sin, sin, cos, 0, 0)
+    }
+
+    /// The exact matrix of `orientation` for a parent area of `size`.
+    pub const fn orientation(orientation: Orientation, size: Size) -> Self {
+        let one = Self::ONE;
+        let width = size.width as i32 * one;
+        let height = size.height as i32 * one;
+
+        match orientation {
+            Orientation::Identity => Self::IDENTITY,
+            Orientation::Rotate90 => Self::new(0, -one, one, 0, width, 0),
+            Orientation::Rotate180 => Self::new(-one, 0, 0, -one, width, height),
+            Orientation::Rotate270 => Self::new(0, one, -one, 0, 0, height),
+            Orientation::FlipHorizontal => Self::new(-one, 0, 0, one, width, 0),
+            Orientation::FlipVertical => Self::new(one, 0, 0, -one, 0, height),
+            Orientation::Transpose => Self::new(0, one, one, 0, 0, 0),
+            Orientation::AntiTranspose => Self::new(0, -one, -one, 0, width, height),
+        }
+    }
+
+    /// Composes two transforms, `self` is applied first.
+    pub const fn then(&self, next: &Self) -> Self {
+        const fn mul(x: i32, y: i32) -> i64 {
+            x as i64 * y as i64
+        }
+
+        let shift = Self::FRAC_BITS;
+
+        Self::new(
+            ((mul(next.a, self.a) + mul(next.b, self.c)) >> shift) as i32,
+            ((mul(next.a, self.b) + mul(next.b, self.d)) >> shift) as i32,
+            ((mul(next.c, self.a) + mul(next.d, self.c)) >> shift) as i32,
+            ((mul(next.c, self.b) + mul(next.d, self.d)) >> shift) as i32,
+            ((mul(next.a, self.tx) + mul(next.b, self.ty)) >> shift) as i32 + next.tx,
+            ((mul(next.c, self.tx) + mul(next.d, self.ty)) >> shift) as i32 + next.ty,
+        )
+    }
+
+    pub const fn is_axis_aligned(&self) -> bool {
+        self.b == 0 && self.c == 0
+    }
+
+    const fn determinant(&self) -> i128 {
+        self.a as i128 * self.d as i128 - self.b as i128 * self.c as i128
+    }
+
+    /// Maps a fixed point logical position onto a fixed point parent position.
+    fn map(&self, x: i64, y: i64) -> (i64, i64) {
+        let shift = Self::FRAC_BITS;
+
+        (
+            ((self.a as i64 * x + self.b as i64 * y) >> shift) + self.tx as i64,
+            ((self.c as i64 * x + self.d as i64 * y) >> shift) + self.ty as i64,
+        )
+    }
+
+    /// Maps a fixed point parent position back onto the logical plane, the result is returned as
+    /// numerators over a common positive denominator.
+    fn unmap(&self, x: i64, y: i64) -> Option<(i128, i128, i128)> {
+        let det = self.determinant();
+        if det == 0 {
+            return None;
+        }
+
+        let x = (x - self.tx as i64) as i128;
+        let y = (y - self.ty as i64) as i128;
+
+        let nx = self.d as i128 * x - self.b as i128 * y;
+        let ny = self.a as i128 * y - self.c as i128 * x;
+
+        Some(if det < 0 {
+            (-nx, -ny, -det)
+        } else {
+            (nx, ny, det)
+        })
+    }
+
+    /// The logical pixel whose area contains the center of the parent pixel `point`.
+    fn logical_pixel(&self, point: Point) -> Option<Point> {
+        let half = Self::ONE as i64 / 2;
+
+        let (nx, ny, den) = self.unmap(
+            point.x as i64 * Self::ONE as i64 + half,
+            point.y as i64 * Self::ONE as i64 + half,
+        )?;
+
+        Some(Point::new(
+            nx.div_euclid(den) as i32,
+            ny.div_euclid(den) as i32,
+        ))
+    }
+
+    /// Parent pixels touched by the image of the logical `area`.
+    fn transform_rect(&self, area: &Rectangle) -> Rectangle {
+        let one = Self::ONE as i64;
+
+        let x0 = area.top_left.x as i64 * one;
+        let y0 = area.top_left.y as i64 * one;
+        let x1 = x0 + area.size.width as i64 * one;
+        let y1 = y0 + area.size.height as i64 * one;
+
+        Self::enclosing([
+            self.map(x0, y0),
+            self.map(x1, y0),
+            self.map(x0, y1),
+            self.map(x1, y1),
+        ])
+    }
+
+    /// Logical pixels touched by the preimage of the parent `area`.
+    fn inverse_transform_rect(&self, area: &Rectangle) -> Rectangle {
+        let one = Self::ONE as i64;
+
+        let x0 = area.top_left.x as i64 * one;
+        let y0 = area.top_left.y as i64 * one;
+        let x1 = x0 + area.size.width as i64 * one;
+        let y1 = y0 + area.size.height as i64 * one;
+
+        let corners = [(x0, y0), (x1, y0), (x0, y1), (x1, y1)];
+
+        let mut points = [(0, 0); 4];
+        for (point, (x, y)) in points.iter_mut().zip(corners) {
+            match self.unmap(x, y) {
+                Some((nx, ny, den)) => {
+                    *point = (
+                        (nx * one as i128 / den) as i64,
+                        (ny * one as i128 / den) as i64,
+                    )
+                }
+                None => return Rectangle::new(Point::zero(), Size::zero()),
+            }
+        }
+
+        Self::enclosing(points)
+    }
+
+    fn enclosing(points: [(i64, i64); 4]) -> Rectangle {
+        let one = Self::ONE as i64;
+
+        let left = points.iter().map(|(x, _)| *x).min().unwrap_or(0);
+        let right = points.iter().map(|(x, _)| *x).max().unwrap_or(0);
+        let top = points.iter().map(|(_, y)| *y).min().unwrap_or(0);
+        let bottom = points.iter().map(|(_, y)| *y).max().unwrap_or(0);
+
+        let left = left.div_euclid(one);
+        let top = top.div_euclid(one);
+        let right = (right + one - 1).div_euclid(one);
+        let bottom = (bottom + one - 1).div_euclid(one);
+
+        Rectangle::new(
+            Point::new(left as i32, top as i32),
+            Size::new((right - left) as u32, (bottom - top) as u32),
+        )
+    }
+}
+
+impl Default for AffineMatrix {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+pub struct Affine<'a, T>
+where
+    T: DrawTarget,
+{
+    parent: &'a mut T,
+    matrix: AffineMatrix,
+    clip: Option<Rectangle>,
+}
+
+impl<'a, T> Affine<'a, T>
+where
+    T: DrawTarget,
+{
+    pub fn new(parent: &'a mut T, matrix: AffineMatrix) -> Self {
+        Self {
+            parent,
+            matrix,
+            clip: None,
+        }
+    }
+
+    /// Like `new`, but only draws inside `clip`, given relative to the parent's top-left corner.
+    pub fn with_clip(parent: &'a mut T, matrix: AffineMatrix, clip: Rectangle) -> Self {
+        Self {
+            parent,
+            matrix,
+            clip: Some(clip),
+        }
+    }
+
+    /// The drawable part of the parent, and the parent's own bounding box.
+    fn visible(&self) -> (Rectangle, Rectangle) {
+        let pdim = self.parent.bounding_box();
+
+        let visible = match self.clip {
+            Some(clip) => {
+                Rectangle::new(pdim.top_left + clip.top_left, clip.size).intersection(&pdim)
+            }
+            None => pdim,
+        };
+
+        (visible, pdim)
+    }
+
+    /// Parent pixels covered by the logical `area`, the matrix maps relative to `pdim`.
+    fn covered(
+        matrix: AffineMatrix,
+        area: Rectangle,
+        (visible, pdim): (Rectangle, Rectangle),
+    ) -> impl Iterator<Item = Point> {
+        let bounds = matrix.transform_rect(&area);
+        let bounds =
+            Rectangle::new(pdim.top_left + bounds.top_left, bounds.size).intersection(&visible);
+
+        bounds.points().filter(move |point| {
+            matrix
+                .logical_pixel(*point - pdim.top_left)
+                .map(|pixel| area.contains(pixel))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Like `covered`, for an axis aligned `matrix`. The parent pixels touched by the image of
+    /// `area` form a rectangle and only its outermost rows and columns can have their centers
+    /// outside the image, so checking the corners is enough.
+    fn covered_rect(
+        matrix: AffineMatrix,
+        area: Rectangle,
+        (visible, pdim): (Rectangle, Rectangle),
+    ) -> Option<Rectangle> {
+        let bounds = matrix.transform_rect(&area);
+        let bounds =
+            Rectangle::new(pdim.top_left + bounds.top_left, bounds.size).intersection(&visible);
+        let (top_left, bottom_right) = (bounds.top_left, bounds.bottom_right()?);
+        let area_end = area.top_left + area.size;
+
+        // Logical x only depends on the parent x, and logical y on the parent y
+        let logical = |point: Point| matrix.logical_pixel(point - pdim.top_left);
+        let column = |x: i32| {
+            logical(Point::new(x, top_left.y))
+                .is_some_and(|pixel| (area.top_left.x..area_end.x).contains(&pixel.x))
+        };
+        let row = |y: i32| {
+            logical(Point::new(top_left.x, y))
+                .is_some_and(|pixel| (area.top_left.y..area_end.y).contains(&pixel.y))
+        };
+
+        let left = top_left.x + !column(top_left.x) as i32;
+        let right = bottom_right.x - !column(bottom_right.x) as i32;
+        let top = top_left.y + !row(top_left.y) as i32;
+        let bottom = bottom_right.y - !row(bottom_right.y) as i32;
+
+        (left <= right && top <= bottom)
+            .then(|| Rectangle::with_corners(Point::new(left, top), Point::new(right, bottom)))
+    }
+
+    fn draw_pixels<I>(&mut self, pixels: I) -> Result<(), T::Error>
+    where
+        I: IntoIterator<Item = Pixel<T::Color>>,
+    {
+        let visible = self.visible();
+        let matrix = self.matrix;
+
+        self.parent
+            .draw_iter(pixels.into_iter().flat_map(|Pixel(point, color)| {
+                Self::covered(matrix, Rectangle::new(point, Size::new(1, 1)), visible)
+                    .map(move |point| Pixel(point, color))
+            }))
+    }
+}
+
+impl<'a, T> DrawTarget for Affine<'a, T>
+where
+    T: DrawTarget,
+{
+    type Error = T::Error;
+    type Color = T::Color;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.draw_pixels(pixels)
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.draw_pixels(
+            area.points()
+                .zip(colors)
+                .map(|(point, color)| Pixel(point, color)),
+        )
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let visible = self.visible();
+        let matrix = self.matrix;
+
+        if matrix.is_axis_aligned() {
+            if let Some(covered) = Self::covered_rect(matrix, *area, visible) {
+                return self.parent.fill_solid(&covered, color);
+            }
+
+            return Ok(());
+        }
+
+        self.parent
+            .draw_iter(Self::covered(matrix, *area, visible).map(|point| Pixel(point, color)))
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        match self.clip {
+            Some(_) => {
+                let (visible, _) = self.visible();
+
+                self.parent.fill_solid(&visible, color)
+            }
+            None => self.parent.clear(color),
+        }
+    }
+}
+
+impl<'a, T> Dimensions for Affine<'a, T>
+where
+    T: DrawTarget,
+{
+    fn bounding_box(&self) -> Rectangle {
+        let pdim = self.parent.bounding_box();
+
+        self.matrix
+            .inverse_transform_rect(&Rectangle::new(Point::zero(), pdim.size))
+    }
+}
+
+//
+// Pipeline
+//
+
+/// One geometric transform of a pipeline, mapping logical coordinates onto the coordinates of
+/// the stage below it.
+#[derive(Copy, Clone, Debug)]
+enum Stage {
+    /// Moves every point by the offset, for translating and cropping.
+    Offset(Point),
+    /// Orients the view onto the area below.
+    Orient(Orientation, Rectangle),
+    /// Scales the view of the given size onto the area below, like [`Scaled`].
+    Scale(Size, Rectangle),
+    /// Discards everything outside the area.
+    Clip(Rectangle),
+    /// Adjacent affine transforms are folded into one matrix.
+    Affine(AffineMatrix),
+}
+
+/// The stages of a pipeline, from the target up, and the resulting logical bounding box.
+/// Integer stages are kept apart so that translating, cropping, orienting and scaling stay exact.
+#[derive(Clone, Debug)]
+struct Geometry {
+    stages: Vec<Stage>,
+    bounds: Rectangle,
+}
+
+impl Geometry {
+    fn new(size: Size) -> Self {
+        Self {
+            stages: Vec::new(),
+            bounds: Rectangle::new(Point::zero(), size),
+        }
+    }
+
+    /// Adds a new outer stage, `stage` maps its coordinates onto the current logical ones.
+    fn push(mut self, stage: Stage, bounds: Rectangle) -> Self {
+        match (self.stages.last_mut(), stage) {
+            (Some(Stage::Offset(offset)), Stage::Offset(next)) => *offset += next,
+            (Some(Stage::Affine(matrix)), Stage::Affine(next)) => *matrix = next.then(matrix),
+            _ => self.stages.push(stage),
+        }
+
+        Self { bounds, ..self }
+    }
+
+    /// The total offset if every stage only translates or clips, which keeps contiguous fills
+    /// contiguous.
+    fn translation(&self) -> Option<Point> {
+        self.stages
+            .iter()
+            .try_fold(Point::zero(), |total, stage| match stage {
+                Stage::Offset(offset) => Some(total + *offset),
+                Stage::Clip(_) => Some(total),
+                _ => None,
+            })
+    }
+
+    /// Whether every stage maps rectangles onto rectangles.
+    fn is_rectilinear(&self) -> bool {
+        self.stages.iter().all(|stage| match stage {
+            Stage::Affine(matrix) => matrix.is_axis_aligned(),
+            _ => true,
+        })
+    }
+}
+
+pub struct PipelineBuilder<T, F> {
+    target: T,
+    geometry: Geometry,
+    flusher: F,
+}
+
+impl<T> PipelineBuilder<T, NoopFlusher<T>>
+where
+    T: DrawTarget,
+{
+    pub fn new(target: T) -> Self {
+        let size = target.bounding_box().size;
+
+        Self {
+            target,
+            geometry: Geometry::new(size),
+            flusher: |_| Ok(()),
+        }
+    }
+}
+
+impl<T, F> PipelineBuilder<T, F>
+where
+    T: DrawTarget,
+{
+    pub fn translate(self, offset: Point) -> Self {
+        let bounds = self.geometry.bounds;
+
+        self.push(
+            Stage::Offset(offset),
+            Rectangle::new(bounds.top_left - offset, bounds.size),
+        )
+    }
+
+    /// Like [`DrawTargetExt::cropped`] this only moves the origin and shrinks the bounding box,
+    /// use [`clip`](Self::clip) to also discard drawing outside `area`.
+    pub fn crop(self, area: &Rectangle) -> Self {
+        let area = area.intersection(&self.geometry.bounds);
+
+        self.push(
+            Stage::Offset(area.top_left),
+            Rectangle::new(Point::zero(), area.size),
+        )
+    }
+
+    pub fn clip(self, area: &Rectangle) -> Self {
+        let bounds = self.geometry.bounds;
+
+        self.push(Stage::Clip(*area), bounds)
+    }
+
+    pub fn rotate(self, angle: RotateAngle) -> Self {
+        self.orient(angle.into())
+    }
+
+    pub fn orient(self, orientation: Orientation) -> Self {
+        let bounds = self.geometry.bounds;
+
+        self.push(
+            Stage::Orient(orientation, bounds),
+            Rectangle::new(Point::zero(), orientation.transform_size(bounds.size)),
+        )
+    }
+
+    pub fn scale(self, size: Size) -> Self {
+        let bounds = self.geometry.bounds;
+
+        self.push(
+            Stage::Scale(size, bounds),
+            Rectangle::new(Point::zero(), size),
+        )
+    }
+
+    /// Like [`Affine`], `matrix` maps relative to the current top-left corner.
+    pub fn affine(self, matrix: AffineMatrix) -> Self {
+        let bounds = self.geometry.bounds;
+        let size = Rectangle::new(Point::zero(), bounds.size);
+
+        let this = if bounds.top_left == Point::zero() {
+            self
+        } else {
+            self.push(Stage::Offset(bounds.top_left), size)
+        };
+
+        this.push(Stage::Affine(matrix), matrix.inverse_transform_rect(&size))
+    }
+
+    pub fn flushing<G>(self, flusher: G) -> PipelineBuilder<T, G>
+    where
+        G: FnMut(&mut T) -> Result<(), T::Error>,
+    {
+        PipelineBuilder {
+            target: self.target,
+            geometry: self.geometry,
+            flusher,
+        }
+    }
+
+    /// Flushes the wrapped target itself when the pipeline is flushed.
+    pub fn flushing_target(self) -> PipelineBuilder<T, NoopFlusher<T>>
+    where
+        T: Flushable,
+    {
+        self.flushing(T::flush)
+    }
+
+    pub fn build(self) -> Owned<PipelineT<T, F>> {
+        PipelineT(self.target, self.geometry, self.flusher).into_owned()
+    }
+
+    fn push(self, stage: Stage, bounds: Rectangle) -> Self {
+        Self {
+            geometry: self.geometry.push(stage, bounds),
+            ..self
+        }
+    }
+}
+
+pub struct PipelineT<T, F>(T, Geometry, F);
+
+impl<T, F> Transformer for PipelineT<T, F>
+where
+    T: DrawTarget,
+{
+    type Color = T::Color;
+    type Error = T::Error;
+
+    type DrawTarget<'a> = Pipeline<'a, T, F> where Self: 'a;
+
+    fn transform<'a>(&'a mut self) -> Self::DrawTarget<'a> {
+        Pipeline {
+            parent: &mut self.0,
+            geometry: &self.1,
+            flusher: &mut self.2,
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Rectangle> {
+        Some(self.1.bounds)
+    }
+}
+
+inner_accessors!(PipelineT<T, F>);
+
+pub struct Pipeline<'a, T, F> {
+    parent: &'a mut T,
+    geometry: &'a Geometry,
+    flusher: &'a mut F,
+}
+
+impl<'a, T, F> Pipeline<'a, T, F>
+where
+    T: DrawTarget,
+{
+    /// Maps `area` through a single stage, `None` if nothing is left of it. Sheared and rotated
+    /// affine stages aren't rectilinear and are only handled by
+    /// [`for_each_rect`](Self::for_each_rect).
+    fn map_stage(stage: &Stage, area: Rectangle) -> Option<Rectangle> {
+        let area = match stage {
+            Stage::Offset(offset) => Rectangle::new(area.top_left + *offset, area.size),
+            Stage::Orient(orientation, pdim) => orientation.transform_rect(&area, pdim),
+            Stage::Scale(size, pdim) => Scaled::<T>::transform_rect(&area, *size, pdim),
+            Stage::Clip(clip) => area.intersection(clip),
+            Stage::Affine(matrix) => {
+                Affine::<T>::covered_rect(*matrix, area, Self::unclipped(matrix, &area))?
+            }
+        };
+
+        (!area.is_zero_sized()).then_some(area)
+    }
+
+    /// Maps `area` through every stage of a rectilinear pipeline.
+    fn map_rect(geometry: &Geometry, area: Rectangle) -> Option<Rectangle> {
+        geometry
+            .stages
+            .iter()
+            .rev()
+            .try_fold(area, |area, stage| Self::map_stage(stage, area))
+    }
+
+    /// Maps `area` through `stages`, outermost last, calling `f` with every parent rectangle it
+    /// covers. Non-rectilinear affine stages split the area into single pixels.
+    fn for_each_rect<E>(
+        stages: &[Stage],
+        area: Rectangle,
+        f: &mut impl FnMut(Rectangle) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let Some((stage, inner)) = stages.split_last() else {
+            return f(area);
+        };
+
+        match stage {
+            Stage::Affine(matrix) if !matrix.is_axis_aligned() => {
+                for point in Affine::<T>::covered(*matrix, area, Self::unclipped(matrix, &area)) {
+                    Self::for_each_rect(inner, Rectangle::new(point, Size::new(1, 1)), f)?;
+                }
+
+                Ok(())
+            }
+            stage => match Self::map_stage(stage, area) {
+                Some(area) => Self::for_each_rect(inner, area, f),
+                None => Ok(()),
+            },
+        }
+    }
+
+    /// The visible area and origin for an affine stage, which doesn't clip by itself.
+    fn unclipped(matrix: &AffineMatrix, area: &Rectangle) -> (Rectangle, Rectangle) {
+        (matrix.transform_rect(area), Rectangle::zero())
+    }
+}
+
+impl<'a, T, F> DrawTarget for Pipeline<'a, T, F>
+where
+    T: DrawTarget,
+{
+    type Error = T::Error;
+    type Color = T::Color;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        if self.geometry.is_rectilinear() {
+            let geometry = self.geometry;
+
+            return self
+                .parent
+                .draw_iter(pixels.into_iter().flat_map(|Pixel(point, color)| {
+                    Self::map_rect(geometry, Rectangle::new(point, Size::new(1, 1)))
+                        .into_iter()
+                        .flat_map(|block| block.points())
+                        .map(move |point| Pixel(point, color))
+                }));
+        }
+
+        for Pixel(point, color) in pixels {
+            self.fill_solid(&Rectangle::new(point, Size::new(1, 1)), color)?;
+        }
+
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        if let Some(offset) = self.geometry.translation() {
+            let Some(mapped) = Self::map_rect(self.geometry, *area) else {
+                return Ok(());
+            };
+            let visible = Rectangle::new(mapped.top_left - offset, mapped.size);
+
+            return self.parent.fill_contiguous(
+                &mapped,
+                area.points()
+                    .zip(colors)
+                    .filter(|(point, _)| visible.contains(*point))
+                    .map(|(_, color)| color),
+            );
+        }
+
+        self.draw_iter(
+            area.points()
+                .zip(colors)
+                .map(|(point, color)| Pixel(point, color)),
+        )
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let parent = &mut *self.parent;
+
+        Self::for_each_rect(&self.geometry.stages, *area, &mut |area| {
+            parent.fill_solid(&area, color)
+        })
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        let bounds = self.geometry.bounds;
+
+        self.fill_solid(&bounds, color)
+    }
+}
+
+impl<'a, T, F> Dimensions for Pipeline<'a, T, F> {
+    fn bounding_box(&self) -> Rectangle {
+        self.geometry.bounds
+    }
+}
+
+impl<'a, T, F> Flushable for Pipeline<'a, T, F>
+where
+    T: DrawTarget,
+    F: FnMut(&mut T) -> Result<(), T::Error>,
+{
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        (self.flusher)(self.parent)
+    }
+}
+
+//
+// Tiled
+//
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct TileSizeError {
+    pub index: usize,
+    pub expected: Size,
+    pub actual: Size,
+}
+
+impl core::fmt::Display for TileSizeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Tile {} is {}x{}, expected {}x{} like the first tile",
+            self.index,
+            self.actual.width,
+            self.actual.height,
+            self.expected.width,
+            self.expected.height
+        )
+    }
+}
+
+/// Identical targets presented as one canvas. Tiles are laid out row-major, `columns` per row,
+/// positions not covered by a tile (an incomplete last row) discard drawing.
+pub struct Tiled<T, S> {
+    tiles: S,
+    columns: usize,
+    tile_size: Size,
+    _tile: PhantomData<T>,
+}
+
+impl<T, S> Tiled<T, S>
+where
+    T: DrawTarget,
+    S: AsRef<[T]> + AsMut<[T]>,
+{
+    pub fn new(tiles: S, columns: usize) -> Result<Self, TileSizeError> {
+        let slice = tiles.as_ref();
+        let tile_size = slice
+            .first()
+            .map_or(Size::zero(), |tile| tile.bounding_box().size);
+
+        if let Some((index, actual)) = slice
+            .iter()
+            .map(|tile| tile.bounding_box().size)
+            .enumerate()
+            .find(|(_, size)| *size != tile_size)
+        {
+            return Err(TileSizeError {
+                index,
+                expected: tile_size,
+                actual,
+            });
+        }
+
+        Ok(Self {
+            tiles,
+            columns: max(columns, 1),
+            tile_size,
+            _tile: PhantomData,
+        })
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn rows(&self) -> usize {
+        Self::div_ceil(self.tiles.as_ref().len(), self.columns)
+    }
+
+    pub fn tile_size(&self) -> Size {
+        self.tile_size
+    }
+
+    pub fn tiles(&self) -> &[T] {
+        self.tiles.as_ref()
+    }
+
+    pub fn tiles_mut(&mut self) -> &mut [T] {
+        self.tiles.as_mut()
+    }
+
+    pub fn into_inner(self) -> S {
+        self.tiles
+    }
+
+    /// The area of tile `index` on the canvas.
+    pub fn tile_area(&self, index: usize) -> Rectangle {
+        let (width, height) = (self.tile_size.width as i32, self.tile_size.height as i32);
+        let (column, row) = (index % self.columns, index / self.columns);
+
+        Rectangle::new(
+            Point::new(column as i32 * width, row as i32 * height),
+            self.tile_size,
+        )
+    }
+
+    /// Index of the tile covering `point`, if there is one.
+    pub fn tile_at(&self, point: Point) -> Option<usize> {
+        if !self.bounding_box().contains(point) {
+            return None;
+        }
+
+        let column = (point.x / self.tile_size.width as i32) as usize;
+        let row = (point.y / self.tile_size.height as i32) as usize;
+        let index = row * self.columns + column;
+
+        (index < self.tiles.as_ref().len()).then_some(index)
+    }
+
+    /// Offset from canvas coordinates to the coordinates of tile `index`.
+    fn tile_offset(&self, index: usize) -> Point {
+        self.tiles.as_ref()[index].bounding_box().top_left - self.tile_area(index).top_left
+    }
+
+    fn div_ceil(value: usize, divisor: usize) -> usize {
+        (value + divisor - 1) / divisor
+    }
+}
+
+impl<T, S> DrawTarget for Tiled<T, S>
+where
+    T: DrawTarget,
+    S: AsRef<[T]> + AsMut<[T]>,
+{
+    type Color = T::Color;
+    type Error = T::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let mut pixels = pixels.into_iter().peekable();
+
+        while let Some(Pixel(point, _)) = pixels.peek() {
+            let Some(index) = self.tile_at(*point) else {
+                pixels.next();
+                continue;
+            };
+
+            // Forward the whole run of pixels falling onto the same tile in one call.
+            let area = self.tile_area(index);
+            let offset = self.tile_offset(index);
+
+            self.tiles.as_mut()[index].draw_iter(core::iter::from_fn(|| {
+                pixels
+                    .next_if(|pixel| area.contains(pixel.0))
+                    .map(|pixel| Pixel(pixel.0 + offset, pixel.1))
+            }))?;
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        for index in 0..self.tiles.as_ref().len() {
+            let part = area.intersection(&self.tile_area(index));
+
+            if !part.is_zero_sized() {
+                let offset = self.tile_offset(index);
+
+                self.tiles.as_mut()[index]
+                    .fill_solid(&Rectangle::new(part.top_left + offset, part.size), color)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        for tile in self.tiles.as_mut() {
+            tile.clear(color)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T, S> Dimensions for Tiled<T, S>
+where
+    T: DrawTarget,
+    S: AsRef<[T]> + AsMut<[T]>,
+{
+    fn bounding_box(&self) -> Rectangle {
+        let columns = self.columns.min(self.tiles.as_ref().len());
+
+        Rectangle::new(
+            Point::zero(),
+            Size::new(
+                self.tile_size.width * columns as u32,
+                self.tile_size.height * self.rows() as u32,
+            ),
+        )
+    }
+}
+
+impl<T, S> Flushable for Tiled<T, S>
+where
+    T: Flushable,
+    S: AsRef<[T]> + AsMut<[T]>,
+{
+    /// Flushes every tile, even after one of them failed, and returns the first error.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.tiles
+            .as_mut()
+            .iter_mut()
+            .map(|tile| tile.flush())
+            .fold(Ok(()), Result::and)
+    }
+}
+
+//
+// Tee
+//
+
+#[derive(Debug)]
+pub enum TeeError<A, B> {
+    First(A),
+    Second(B),
+    Both(A, B),
+}
+
+impl<A, B> TeeError<A, B> {
+    fn check(first: Result<(), A>, second: Result<(), B>) -> Result<(), Self> {
+        match (first, second) {
+            (Ok(()), Ok(())) => Ok(()),
+            (Err(a), Ok(())) => Err(Self::First(a)),
+            (Ok(()), Err(b)) => Err(Self::Second(b)),
+            (Err(a), Err(b)) => Err(Self::Both(a, b)),
+        }
+    }
+}
+
+impl<A, B> core::fmt::Display for TeeError<A, B>
+where
+    A: Debug,
+    B: Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::First(a) => write!(f, "First target error: {:?}", a),
+            Self::Second(b) => write!(f, "Second target error: {:?}", b),
+            Self::Both(a, b) => write!(
+                f,
+                "First target error: {:?}, second target error: {:?}",
+                a, b
+            ),
+        }
+    }
+}
+
+/// Forwards every drawing operation to two targets of the same colour type, e.g. a panel and a
+/// simulator. Both targets are always drawn to, their errors are combined in [`TeeError`].
+/// Chain tees or use [`FanOut`] to reach more targets. The bounding box is the one of the first
+/// target.
+pub struct Tee<'a, A, B> {
+    first: &'a mut A,
+    second: &'a mut B,
+}
+
+impl<'a, A, B> Tee<'a, A, B>
+where
+    A: DrawTarget,
+    B: DrawTarget<Color = A::Color>,
+{
+    pub fn new(first: &'a mut A, second: &'a mut B) -> Self {
+        Self { first, second }
+    }
+
+    fn draw_chunk(
+        &mut self,
+        pixels: &[Pixel<A::Color>],
+    ) -> Result<(), TeeError<A::Error, B::Error>> {
+        TeeError::check(
+            self.first.draw_iter(pixels.iter().copied()),
+            self.second.draw_iter(pixels.iter().copied()),
+        )
+    }
+}
+
+impl<'a, A, B> DrawTarget for Tee<'a, A, B>
+where
+    A: DrawTarget,
+    B: DrawTarget<Color = A::Color>,
+{
+    type Color = A::Color;
+    type Error = TeeError<A::Error, B::Error>;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        // The iterator can only be consumed once, so hand it out in fixed-size chunks.
+        for_each_chunk(pixels, |chunk| self.draw_chunk(chunk))
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        for_each_contiguous_chunk(area, colors, |piece, colors| {
+            TeeError::check(
+                self.first.fill_contiguous(piece, colors.iter().copied()),
+                self.second.fill_contiguous(piece, colors.iter().copied()),
+            )
+        })
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        TeeError::check(
+            self.first.fill_solid(area, color),
+            self.second.fill_solid(area, color),
+        )
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        TeeError::check(self.first.clear(color), self.second.clear(color))
+    }
+}
+
+impl<'a, A, B> Dimensions for Tee<'a, A, B>
+where
+    A: Dimensions,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.first.bounding_box()
+    }
+}
+
+impl<'a, A, B> Flushable for Tee<'a, A, B>
+where
+    A: Flushable,
+    B: Flushable<Color = A::Color>,
+{
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        TeeError::check(self.first.flush(), self.second.flush())
+    }
+}
+
+/// The first error of a [`FanOut`] and how many targets failed.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FanOutError<E> {
+    /// Index of the first target that failed.
+    pub index: usize,
+    pub error: E,
+    pub failed: usize,
+}
+
+impl<E> core::fmt::Display for FanOutError<E>
+where
+    E: Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} of the targets failed, the first one ({}) with {:?}",
+            self.failed, self.index, self.error
+        )
+    }
+}
+
+/// Forwards every drawing operation to any number of targets of the same type, e.g. a row of
+/// identical panels. Like [`Tee`], every target receives an operation even if an earlier one
+/// fails. The bounding box is the one of the first target.
+pub struct FanOut<'a, T> {
+    targets: &'a mut [T],
+}
+
+impl<'a, T> FanOut<'a, T>
+where
+    T: DrawTarget,
+{
+    pub fn new(targets: &'a mut [T]) -> Self {
+        Self { targets }
+    }
+
+    fn each<F>(&mut self, mut f: F) -> Result<(), FanOutError<T::Error>>
+    where
+        F: FnMut(&mut T) -> Result<(), T::Error>,
+    {
+        let mut result = Ok(());
+
+        for (index, target) in self.targets.iter_mut().enumerate() {
+            if let Err(error) = f(target) {
+                match &mut result {
+                    Ok(()) => {
+                        result = Err(FanOutError {
+                            index,
+                            error,
+                            failed: 1,
+                        })
+                    }
+                    Err(first) => first.failed += 1,
+                }
+            }
+        }
+
+        result
+    }
+}
+
+impl<'a, T> DrawTarget for FanOut<'a, T>
+where
+    T: DrawTarget,
+{
+    type Color = T::Color;
+    type Error = FanOutError<T::Error>;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for_each_chunk(pixels, |chunk| {
+            self.each(|target| target.draw_iter(chunk.iter().copied()))
+        })
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        for_each_contiguous_chunk(area, colors, |piece, colors| {
+            self.each(|target| target.fill_contiguous(piece, colors.iter().copied()))
+        })
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.each(|target| target.fill_solid(area, color))
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.each(|target| target.clear(color))
+    }
+}
+
+/// Without targets the bounding box is empty.
+impl<'a, T> Dimensions for FanOut<'a, T>
+where
+    T: Dimensions,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.targets
+            .first()
+            .map_or(Rectangle::zero(), Dimensions::bounding_box)
+    }
+}
+
+impl<'a, T> Flushable for FanOut<'a, T>
+where
+    T: Flushable,
+{
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.each(Flushable::flush)
+    }
+}
+
+//
+// Layers
+//
+
+/// Which pixels of a [`Layer`] hide the layers below it.
+pub enum Transparency<C, M> {
+    Opaque,
+    /// Pixels of this colour are see-through.
+    Key(C),
+    /// A mask in the layer's coordinates, e.g. a `PackedFramebuffer<BinaryColor, _>`.
+    /// `BinaryColor::On` pixels are opaque, pixels outside the mask are see-through.
+    Mask(M),
+}
+
+/// An off-screen framebuffer composited by [`Layers`].
+pub struct Layer<C, S, M = PackedFramebuffer<BinaryColor, S>> {
+    framebuffer: PackedFramebuffer<C, S>,
+    transparency: Transparency<C, M>,
+    offset: Point,
+    z: i32,
+    visible: bool,
+}
+
+impl<C, S> Layer<C, S>
+where
+    C: PackedColor,
+    S: AsRef<[u8]> + AsMut<[u8]>,
+{
+    /// An opaque layer, masks are packed framebuffers sharing the layer's storage type.
+    pub fn new(framebuffer: PackedFramebuffer<C, S>) -> Self {
+        Self::with(framebuffer, Transparency::Opaque)
+    }
+}
+
+impl<C, S, M> Layer<C, S, M>
+where
+    C: PackedColor,
+    S: AsRef<[u8]> + AsMut<[u8]>,
+    M: ReadableTarget<Color = BinaryColor>,
+{
+    /// A layer whose opaque pixels are the `BinaryColor::On` pixels of `mask`.
+    pub fn masked(framebuffer: PackedFramebuffer<C, S>, mask: M) -> Self {
+        Self::with(framebuffer, Transparency::Mask(mask))
+    }
+
+    fn with(framebuffer: PackedFramebuffer<C, S>, transparency: Transparency<C, M>) -> Self {
+        Self {
+            framebuffer,
+            transparency,
+            offset: Point::zero(),
+            z: 0,
+            visible: true,
+        }
+    }
+
+    pub fn with_transparency(self, transparency: Transparency<C, M>) -> Self {
+        Self {
+            transparency,
+            ..self
+        }
+    }
+
+    pub fn with_offset(self, offset: Point) -> Self {
+        Self { offset, ..self }
+    }
+
+    /// Layers with a higher `z` are drawn on top, ties are broken by position in [`Layers`].
+    pub fn with_z(self, z: i32) -> Self {
+        Self { z, ..self }
+    }
+
+    pub fn with_visible(self, visible: bool) -> Self {
+        Self { visible, ..self }
+    }
+
+    pub fn framebuffer(&self) -> &PackedFramebuffer<C, S> {
+        &self.framebuffer
+    }
+
+    pub fn transparency(&self) -> &Transparency<C, M> {
+        &self.transparency
+    }
+
+    pub fn offset(&self) -> Point {
+        self.offset
+    }
+
+    pub fn z(&self) -> i32 {
+        self.z
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn into_inner(self) -> PackedFramebuffer<C, S> {
+        self.framebuffer
+    }
+
+    /// The area covered by the layer, in the coordinates of the composited target.
+    pub fn area(&self) -> Rectangle {
+        Rectangle::new(self.offset, self.framebuffer.bounding_box().size)
+    }
+
+    /// The opaque colour this layer contributes at `point` of the composited target.
+    fn color_at(&self, point: Point) -> Option<C> {
+        if !self.visible {
+            return None;
+        }
+
+        let point = point - self.offset;
+        let color = self.framebuffer.get_pixel(point)?;
+
+        let opaque = match &self.transparency {
+            Transparency::Opaque => true,
+            Transparency::Key(key) => color != *key,
+            Transparency::Mask(mask) => mask.get_pixel(point) == Some(BinaryColor::On),
+        };
+
+        opaque.then_some(color)
+    }
+}
+
+/// Off-screen layers composited into a target. Drawing goes to a single layer through
+/// [`layer_mut`](Self::layer_mut) and only the changed areas are composited again by
+/// [`compose`](Self::compose) or [`flush`](Self::flush).
+///
+/// Pixels without any opaque visible layer are filled with the
+/// [background](Self::with_background). Without one they are left untouched on the target, which
+/// then keeps showing a layer after it was hidden or moved away.
+pub struct Layers<T, S, L, M = PackedFramebuffer<BinaryColor, S>>
 where
     T: DrawTarget,
-    T::Color: PixelColor + IntoStorage<Storage = u8> + From<u8>,
 {
-    type Error = T::Error;
+    target: T,
+    layers: L,
+    background: Option<T::Color>,
+    dirty: DirtyRegions,
+    _storage: PhantomData<(S, M)>,
+}
 
-    type Color = T::Color;
+impl<T, S, L, M> Layers<T, S, L, M>
+where
+    T: DrawTarget,
+    T::Color: PackedColor,
+    S: AsRef<[u8]> + AsMut<[u8]>,
+    L: AsRef<[Layer<T::Color, S, M>]> + AsMut<[Layer<T::Color, S, M>]>,
+    M: ReadableTarget<Color = BinaryColor>,
+{
+    pub fn new(target: T, layers: L) -> Self {
+        let mut this = Self {
+            target,
+            layers,
+            background: None,
+            dirty: DirtyRegions::new(),
+            _storage: PhantomData,
+        };
+
+        this.invalidate_all();
+        this
+    }
+
+    /// Fills the pixels no opaque visible layer covers with `color`.
+    pub fn with_background(mut self, color: T::Color) -> Self {
+        self.set_background(Some(color));
+        self
+    }
+
+    pub fn background(&self) -> Option<T::Color> {
+        self.background
+    }
+
+    /// Changes the background, the whole target is composited again on the next
+    /// [`compose`](Self::compose).
+    pub fn set_background(&mut self, background: Option<T::Color>) {
+        self.background = background;
+        self.dirty.add(self.target.bounding_box());
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.as_ref().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.as_ref().is_empty()
+    }
+
+    pub fn layer(&self, index: usize) -> &Layer<T::Color, S, M> {
+        &self.layers.as_ref()[index]
+    }
+
+    /// A draw target for layer `index`, in the layer's own coordinates.
+    pub fn layer_mut(&mut self, index: usize) -> LayerTarget<'_, T::Color, S, M> {
+        LayerTarget {
+            layer: &mut self.layers.as_mut()[index],
+            dirty: &mut self.dirty,
+        }
+    }
+
+    pub fn set_visible(&mut self, index: usize, visible: bool) {
+        self.update(index, |layer| layer.visible = visible);
+    }
+
+    pub fn set_offset(&mut self, index: usize, offset: Point) {
+        self.update(index, |layer| layer.offset = offset);
+    }
+
+    pub fn set_z(&mut self, index: usize, z: i32) {
+        self.update(index, |layer| layer.z = z);
+    }
+
+    pub fn set_transparency(&mut self, index: usize, transparency: Transparency<T::Color, M>) {
+        self.update(index, |layer| layer.transparency = transparency);
+    }
+
+    /// Composites `area` again on the next [`compose`](Self::compose), e.g. after the target
+    /// was drawn to directly.
+    pub fn invalidate(&mut self, area: &Rectangle) {
+        self.dirty.add(*area);
+    }
+
+    pub fn invalidate_all(&mut self) {
+        if self.background.is_some() {
+            self.dirty.add(self.target.bounding_box());
+        }
+
+        for index in 0..self.len() {
+            self.dirty.add(self.layers.as_ref()[index].area());
+        }
+    }
+
+    pub fn target(&self) -> &T {
+        &self.target
+    }
+
+    pub fn target_mut(&mut self) -> &mut T {
+        &mut self.target
+    }
+
+    pub fn into_inner(self) -> (T, L) {
+        (self.target, self.layers)
+    }
+
+    /// Writes the changed areas of the composited layers to the target.
+    pub fn compose(&mut self) -> Result<(), T::Error> {
+        let Self {
+            target,
+            layers,
+            background,
+            dirty,
+            ..
+        } = self;
+
+        let bbox = target.bounding_box();
+        let layers = layers.as_ref();
+        let background = *background;
+
+        for area in dirty.regions() {
+            let area = area.intersection(&bbox);
+
+            target.draw_iter(area.points().filter_map(|point| {
+                Self::color_at(layers, point)
+                    .or(background)
+                    .map(|color| Pixel(point, color))
+            }))?;
+        }
+
+        dirty.clear();
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), T::Error>
+    where
+        T: Flushable,
+    {
+        self.compose()?;
+        self.target.flush()
+    }
+
+    fn update(&mut self, index: usize, change: impl FnOnce(&mut Layer<T::Color, S, M>)) {
+        let layer = &mut self.layers.as_mut()[index];
+        let before = layer.area();
+
+        change(layer);
+
+        let after = layer.area();
+
+        self.dirty.add(before);
+        self.dirty.add(after);
+    }
+
+    fn color_at(layers: &[Layer<T::Color, S, M>], point: Point) -> Option<T::Color> {
+        let mut top: Option<(i32, T::Color)> = None;
+
+        for layer in layers {
+            match (top, layer.color_at(point)) {
+                (Some((z, _)), _) if z > layer.z => {}
+                (_, Some(color)) => top = Some((layer.z, color)),
+                _ => {}
+            }
+        }
+
+        top.map(|(_, color)| color)
+    }
+}
+
+/// Draws into one layer of [`Layers`] and records the touched area for compositing.
+pub struct LayerTarget<'a, C, S, M> {
+    layer: &'a mut Layer<C, S, M>,
+    dirty: &'a mut DirtyRegions,
+}
+
+impl<'a, C, S, M> LayerTarget<'a, C, S, M>
+where
+    C: PackedColor,
+    S: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn mark_dirty(&mut self, area: &Rectangle) {
+        // Hidden layers don't show, `Layers::set_visible` marks their area once shown again.
+        if self.layer.visible {
+            let area = area.intersection(&self.layer.framebuffer.bounding_box());
+
+            self.dirty
+                .add(Rectangle::new(area.top_left + self.layer.offset, area.size));
+        }
+    }
+}
+
+impl<'a, C, S, M> DrawTarget for LayerTarget<'a, C, S, M>
+where
+    C: PackedColor,
+    S: AsRef<[u8]> + AsMut<[u8]>,
+{
+    type Color = C;
+    type Error = Infallible;
 
     fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        self.current.draw_iter(pixels).unwrap();
+        let bbox = self.layer.framebuffer.bounding_box();
+        let mut corners: Option<(Point, Point)> = None;
+
+        self.layer
+            .framebuffer
+            .draw_iter(pixels.into_iter().inspect(|Pixel(point, _)| {
+                if bbox.contains(*point) {
+                    corners = Some(match corners {
+                        Some((tl, br)) => (tl.component_min(*point), br.component_max(*point)),
+                        None => (*point, *point),
+                    });
+                }
+            }))?;
+
+        if let Some((top_left, bottom_right)) = corners {
+            self.mark_dirty(&Rectangle::with_corners(top_left, bottom_right));
+        }
 
         Ok(())
     }
@@ -377,486 +4022,724 @@ where
     where
         I: IntoIterator<Item = Self::Color>,
     {
-        self.current.fill_contiguous(area, colors).unwrap();
+        self.layer.framebuffer.fill_contiguous(area, colors)?;
+        self.mark_dirty(area);
 
         Ok(())
     }
 
     fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
-        self.current.fill_solid(area, color).unwrap();
+        self.layer.framebuffer.fill_solid(area, color)?;
+        self.mark_dirty(area);
 
         Ok(())
     }
 
     fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
-        self.current.clear(color).unwrap();
+        self.layer.framebuffer.clear(color)?;
+        self.mark_dirty(&self.layer.framebuffer.bounding_box());
 
         Ok(())
     }
 }
 
-impl<'a, T> Flushable for Buffered<'a, T>
+impl<'a, C, S, M> OriginDimensions for LayerTarget<'a, C, S, M>
 where
-    T: Flushable,
-    T::Color: PixelColor + IntoStorage<Storage = u8> + From<u8>,
+    C: PackedColor,
+    S: AsRef<[u8]> + AsMut<[u8]>,
 {
-    fn flush(&mut self) -> Result<(), Self::Error> {
-        self.reference.apply(&mut self.current, &mut self.target)?;
-
-        self.target.flush()
+    fn size(&self) -> Size {
+        self.layer.framebuffer.bounding_box().size
     }
 }
 
 //
-// PackedFramebuffer
+// Blended
 //
 
-pub struct PackedFramebuffer<'a, COLOR> {
-    buf: &'a mut [u8],
-    width: usize,
-    height: usize,
-    _color: PhantomData<COLOR>,
+/// A colour with an alpha channel, `0` is fully transparent and `255` fully opaque.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Alpha<C> {
+    pub color: C,
+    pub alpha: u8,
+}
+
+impl<C> Alpha<C> {
+    pub const fn new(color: C, alpha: u8) -> Self {
+        Self { color, alpha }
+    }
+
+    pub const fn opaque(color: C) -> Self {
+        Self::new(color, u8::MAX)
+    }
 }
 
-impl<'a, COLOR> PackedFramebuffer<'a, COLOR>
+impl<C> PixelColor for Alpha<C>
 where
-    COLOR: PixelColor + IntoStorage<Storage = u8> + From<u8>,
+    C: PixelColor,
 {
-    const BITS_PER_PIXEL: usize = Self::bits_per_pixel();
-    const PIXEL_MASK: u8 = ((1 << Self::BITS_PER_PIXEL) - 1) as u8;
-    const PIXELS_PER_BYTE: usize = 8 / Self::BITS_PER_PIXEL;
-    const PIXELS_PER_BYTE_SHIFT: usize = if Self::BITS_PER_PIXEL == 8 {
-        0
-    } else {
-        Self::BITS_PER_PIXEL
-    };
+    type Raw = ();
+}
 
-    pub fn new(buf: &'a mut [u8], width: usize, height: usize) -> Self {
-        Self {
-            buf,
-            width,
-            height,
-            _color: PhantomData,
-        }
+impl<C> From<(C, u8)> for Alpha<C> {
+    fn from((color, alpha): (C, u8)) -> Self {
+        Self::new(color, alpha)
     }
+}
 
-    pub const fn buffer_size(display_size: Size) -> usize {
-        display_size.width as usize * display_size.height as usize / (8 / Self::bits_per_pixel())
-    }
+/// Colours that can be mixed channel by channel.
+pub trait Blend: PixelColor {
+    /// Mixes `self` over `background`, `alpha` being the weight of `self`.
+    fn blend(self, background: Self, alpha: u8) -> Self;
+}
 
-    const fn bits_per_pixel() -> usize {
-        if COLOR::Raw::BITS_PER_PIXEL > 4 {
-            8
-        } else if COLOR::Raw::BITS_PER_PIXEL > 2 {
-            4
-        } else if COLOR::Raw::BITS_PER_PIXEL > 1 {
-            2
+fn mix(foreground: u8, background: u8, alpha: u8) -> u8 {
+    let (foreground, background, alpha) = (foreground as u32, background as u32, alpha as u32);
+
+    ((foreground * alpha + background * (255 - alpha) + 127) / 255) as u8
+}
+
+impl Blend for BinaryColor {
+    fn blend(self, background: Self, alpha: u8) -> Self {
+        if alpha >= 128 {
+            self
         } else {
-            1
+            background
         }
     }
+}
 
-    pub fn apply<D>(&mut self, new: &Self, to: &mut D) -> Result<usize, D::Error>
-    where
-        D: DrawTarget<Color = COLOR>,
-    {
-        let width = self.width();
-        let height = self.height();
-
-        let mut changes = 0_usize;
+macro_rules! impl_blend_gray {
+    ($($color:ty),*) => {$(
+        impl Blend for $color {
+            fn blend(self, background: Self, alpha: u8) -> Self {
+                Self::new(mix(self.luma(), background.luma(), alpha))
+            }
+        }
+    )*};
+}
 
-        let pixels = (0..height)
-            .flat_map(|y| (0..width).map(move |x| (x, y)))
-            .filter_map(|(x, y)| {
-                let bytes_offset = self.y_offset(y as usize) + Self::x_offset(x as usize);
-                let bits_offset = Self::x_bits_offset(x as usize);
+macro_rules! impl_blend_rgb {
+    ($($color:ty),*) => {$(
+        impl Blend for $color {
+            fn blend(self, background: Self, alpha: u8) -> Self {
+                Self::new(
+                    mix(self.r(), background.r(), alpha),
+                    mix(self.g(), background.g(), alpha),
+                    mix(self.b(), background.b(), alpha),
+                )
+            }
+        }
+    )*};
+}
 
-                let color = new.get(bytes_offset, bits_offset);
-                if self.get(bytes_offset, bits_offset) != color {
-                    self.set(bytes_offset, bits_offset, color);
+impl_blend_gray!(Gray2, Gray4, Gray8);
+impl_blend_rgb!(Rgb555, Bgr555, Rgb565, Bgr565, Rgb666, Bgr666, Rgb888, Bgr888);
 
-                    changes += 1;
+/// Blends [`Alpha`] pixels against the current contents of a readable parent target.
+pub struct Blended<'a, T> {
+    parent: &'a mut T,
+}
 
-                    Some(Pixel(Point::new(x as _, y as _), color))
-                } else {
-                    None
-                }
-            });
+impl<'a, T> Blended<'a, T> {
+    pub fn new(parent: &'a mut T) -> Self {
+        Self { parent }
+    }
+}
 
-        to.draw_iter(pixels)?;
+impl<'a, T> DrawTarget for Blended<'a, T>
+where
+    T: ReadableTarget,
+    T::Color: Blend,
+{
+    type Color = Alpha<T::Color>;
+    type Error = T::Error;
 
-        // trace!(
-        //     "Display updated ({}/{} changed pixels)",
-        //     changes,
-        //     width * height
-        // );
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, Alpha { color, alpha }) in pixels {
+            let color = match alpha {
+                0 => continue,
+                u8::MAX => color,
+                _ => match self.parent.get_pixel(point) {
+                    Some(background) => color.blend(background, alpha),
+                    None => continue,
+                },
+            };
+
+            self.parent
+                .draw_iter(core::iter::once(Pixel(point, color)))?;
+        }
 
-        Ok(changes)
+        Ok(())
     }
 
-    fn offsets(&self, area: Rectangle) -> impl Iterator<Item = (usize, usize)> {
-        let dimensions = self.bounding_box();
-        let bottom_right = dimensions.bottom_right().unwrap_or(dimensions.top_left);
-
-        let x = min(max(area.top_left.x, 0), bottom_right.x) as usize;
-        let y = min(max(area.top_left.y, 0), bottom_right.y) as usize;
-
-        let xend = min(
-            max(area.top_left.x + area.size.width as i32, 0),
-            bottom_right.x,
-        ) as usize;
-        let yend = min(
-            max(area.top_left.y + area.size.height as i32, 0),
-            bottom_right.y,
-        ) as usize;
-
-        (self.y_offset(y)..self.y_offset(yend))
-            .step_by(self.bytes_per_row())
-            .flat_map(move |y_offset| {
-                (x..xend).map(move |x| (y_offset + Self::x_offset(x), Self::x_bits_offset(x)))
-            })
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        match color.alpha {
+            0 => Ok(()),
+            u8::MAX => self.parent.fill_solid(area, color.color),
+            _ => self.draw_iter(area.points().map(|point| Pixel(point, color))),
+        }
     }
+}
 
-    #[inline(always)]
-    fn width(&self) -> usize {
-        self.width
-    }
+pub struct BlendedT<T>(T);
 
-    #[inline(always)]
-    fn height(&self) -> usize {
-        self.height
+impl<T> Transformer for BlendedT<T>
+where
+    T: ReadableTarget,
+    T::Color: Blend,
+{
+    type Color = Alpha<T::Color>;
+    type Error = T::Error;
+
+    type DrawTarget<'a> = Blended<'a, T> where Self: 'a;
+
+    fn transform<'a>(&'a mut self) -> Self::DrawTarget<'a> {
+        Blended::new(&mut self.0)
     }
 
-    #[inline(always)]
-    fn to_bits(color: COLOR) -> u8 {
-        color.into_storage()
+    fn bounding_box(&self) -> Option<Rectangle> {
+        Some(self.0.bounding_box())
     }
+}
 
-    #[inline(always)]
-    fn from_bits(bits: u8) -> COLOR {
-        bits.into()
+inner_accessors!(BlendedT<T>);
+
+impl<'a, T> Dimensions for Blended<'a, T>
+where
+    T: Dimensions,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.parent.bounding_box()
     }
+}
 
-    #[inline(always)]
-    fn y_offset(&self, y: usize) -> usize {
-        y * self.bytes_per_row()
+//
+// Export
+//
+
+/// The colours of `target` row by row, exactly filling its bounding box, with `background`
+/// wherever a pixel can't be read. The image writers announce their data length up front, so
+/// they must never write more or fewer pixels than the header says.
+#[cfg(feature = "image-export")]
+fn image_rows<T>(
+    target: &T,
+    background: T::Color,
+) -> impl Iterator<Item = impl Iterator<Item = T::Color> + '_> + '_
+where
+    T: ReadableTarget,
+{
+    let size = target.bounding_box().size;
+
+    target
+        .rows()
+        .map(Some)
+        .chain(core::iter::repeat_with(|| None))
+        .take(size.height as usize)
+        .map(move |row| {
+            row.into_iter()
+                .flatten()
+                .chain(core::iter::repeat(None))
+                .take(size.width as usize)
+                .map(move |color| color.unwrap_or(background))
+        })
+}
+
+/// Writes `target` as a binary PBM (P4) image, `BinaryColor::On` pixels are black. Pixels that
+/// can't be read are written as `background`.
+#[cfg(feature = "image-export")]
+pub fn write_pbm<T, W>(target: &T, background: BinaryColor, mut out: W) -> std::io::Result<()>
+where
+    T: ReadableTarget<Color = BinaryColor>,
+    W: std::io::Write,
+{
+    let size = target.bounding_box().size;
+
+    write!(out, "P4\n{} {}\n", size.width, size.height)?;
+
+    for row in image_rows(target, background) {
+        // Every row starts on a new byte
+        let (mut byte, mut bits) = (0u8, 0);
+
+        for color in row {
+            byte = byte << 1 | color.is_on() as u8;
+            bits += 1;
+
+            if bits == 8 {
+                out.write_all(&[byte])?;
+                (byte, bits) = (0, 0);
+            }
+        }
+
+        if bits > 0 {
+            out.write_all(&[byte << (8 - bits)])?;
+        }
     }
 
-    #[inline(always)]
-    fn x_offset(x: usize) -> usize {
-        x / Self::PIXELS_PER_BYTE
+    Ok(())
+}
+
+/// Writes `target` as a binary PGM (P5) image, keeping the colour's own grey levels. Pixels that
+/// can't be read are written as `background`.
+#[cfg(feature = "image-export")]
+pub fn write_pgm<T, W>(target: &T, background: T::Color, mut out: W) -> std::io::Result<()>
+where
+    T: ReadableTarget,
+    T::Color: GrayColor,
+    W: std::io::Write,
+{
+    let size = target.bounding_box().size;
+
+    write!(
+        out,
+        "P5\n{} {}\n{}\n",
+        size.width,
+        size.height,
+        T::Color::WHITE.luma()
+    )?;
+
+    for row in image_rows(target, background) {
+        for color in row {
+            out.write_all(&[color.luma()])?;
+        }
     }
 
-    #[inline(always)]
-    fn x_bits_offset(x: usize) -> usize {
-        Self::PIXELS_PER_BYTE_SHIFT * (x % Self::PIXELS_PER_BYTE)
+    Ok(())
+}
+
+/// Writes `target` as a binary PPM (P6) image with 8 bits per channel. Pixels that can't be read
+/// are written as `background`.
+#[cfg(feature = "image-export")]
+pub fn write_ppm<T, W>(target: &T, background: T::Color, mut out: W) -> std::io::Result<()>
+where
+    T: ReadableTarget,
+    T::Color: Into<Rgb888>,
+    W: std::io::Write,
+{
+    let size = target.bounding_box().size;
+
+    write!(out, "P6\n{} {}\n255\n", size.width, size.height)?;
+
+    for row in image_rows(target, background) {
+        for color in row {
+            let color: Rgb888 = color.into();
+
+            out.write_all(&[color.r(), color.g(), color.b()])?;
+        }
     }
 
-    #[inline(always)]
-    fn bytes_per_row(&self) -> usize {
-        self.width() / Self::PIXELS_PER_BYTE
+    Ok(())
+}
+
+/// Writes `target` as an 8-bit RGB PNG image, pixels that can't be read are written as
+/// `background`. The image data is stored uncompressed, one deflate block per row, so nothing
+/// has to be buffered.
+#[cfg(feature = "image-export")]
+pub fn write_png<T, W>(target: &T, background: T::Color, mut out: W) -> std::io::Result<()>
+where
+    T: ReadableTarget,
+    T::Color: Into<Rgb888>,
+    W: std::io::Write,
+{
+    let size = target.bounding_box().size;
+    let row_len = 1 + 3 * size.width as usize;
+    let rows = size.height as usize;
+
+    // zlib header, a stored block per row (or one empty block) and the Adler-32 trailer. PNG
+    // chunk lengths are limited to 2^31 - 1 bytes.
+    let data_len = match rows {
+        0 => Some(2 + 5 + 4),
+        _ => rows
+            .checked_mul(5 + row_len)
+            .and_then(|len| len.checked_add(2 + 4)),
+    };
+
+    let data_len = match data_len {
+        Some(len) if row_len <= u16::MAX as usize && len <= i32::MAX as usize => len,
+        _ => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "image too large for uncompressed PNG export",
+            ))
+        }
+    };
+
+    out.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+    let mut header = [0; 13];
+    header[..4].copy_from_slice(&size.width.to_be_bytes());
+    header[4..8].copy_from_slice(&size.height.to_be_bytes());
+    // 8 bits per channel, RGB, default compression, filtering and no interlacing
+    header[8..].copy_from_slice(&[8, 2, 0, 0, 0]);
+
+    let mut chunk = PngChunk::begin(&mut out, b"IHDR", header.len())?;
+    chunk.write(&header)?;
+    chunk.end()?;
+
+    let mut chunk = PngChunk::begin(&mut out, b"IDAT", data_len)?;
+    let mut adler = Adler32::new();
+
+    chunk.write(&[0x78, 0x01])?;
+
+    if rows == 0 {
+        // A deflate stream needs a final block, even an empty one
+        chunk.write(&[1, 0, 0, 0xff, 0xff])?;
     }
 
-    #[inline(always)]
-    fn get(&self, byte_offset: usize, bits_offset: usize) -> COLOR {
-        Self::from_bits((self.buf[byte_offset] >> bits_offset) & Self::PIXEL_MASK)
+    for (y, row) in image_rows(target, background).enumerate() {
+        let len = row_len as u16;
+        let last = (y + 1 == rows) as u8;
+
+        chunk.write(&[last])?;
+        chunk.write(&len.to_le_bytes())?;
+        chunk.write(&(!len).to_le_bytes())?;
+
+        // No filtering
+        adler.update(&[0]);
+        chunk.write(&[0])?;
+
+        for color in row {
+            let color: Rgb888 = color.into();
+            let rgb = [color.r(), color.g(), color.b()];
+
+            adler.update(&rgb);
+            chunk.write(&rgb)?;
+        }
     }
 
-    #[inline(always)]
-    fn set(&mut self, byte_offset: usize, bits_offset: usize, color: COLOR) {
-        let byte = &mut self.buf[byte_offset];
-        *byte &= !(Self::PIXEL_MASK << bits_offset);
-        *byte |= Self::to_bits(color) << bits_offset;
-    }
+    chunk.write(&adler.finish().to_be_bytes())?;
+    chunk.end()?;
+
+    PngChunk::begin(&mut out, b"IEND", 0)?.end()
 }
 
-impl<'a, COLOR> Dimensions for PackedFramebuffer<'a, COLOR>
-where
-    COLOR: PixelColor + IntoStorage<Storage = u8> + From<u8>,
-{
-    fn bounding_box(&self) -> Rectangle {
-        Rectangle::new(
-            Point::zero(),
-            Size::new(self.width() as u32, self.height() as u32),
-        )
-    }
+/// A PNG chunk of known length, checksummed while it is written.
+#[cfg(feature = "image-export")]
+struct PngChunk<'a, W> {
+    out: &'a mut W,
+    crc: u32,
 }
 
-impl<'a, COLOR> DrawTarget for PackedFramebuffer<'a, COLOR>
+#[cfg(feature = "image-export")]
+impl<'a, W> PngChunk<'a, W>
 where
-    COLOR: PixelColor + IntoStorage<Storage = u8> + From<u8>,
+    W: std::io::Write,
 {
-    type Error = Infallible;
+    fn begin(out: &'a mut W, kind: &[u8; 4], len: usize) -> std::io::Result<Self> {
+        out.write_all(&(len as u32).to_be_bytes())?;
 
-    type Color = COLOR;
-
-    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
-    where
-        I: IntoIterator<Item = Pixel<Self::Color>>,
-    {
-        for pixel in pixels {
-            if pixel.0.x >= 0
-                && pixel.0.x < self.width() as _
-                && pixel.0.y >= 0
-                && pixel.0.y < self.height() as _
-            {
-                self.set(
-                    self.y_offset(pixel.0.y as usize) + Self::x_offset(pixel.0.x as usize),
-                    Self::x_bits_offset(pixel.0.x as usize),
-                    pixel.1,
-                );
-            }
-        }
+        let mut chunk = Self { out, crc: !0 };
+        chunk.write(kind)?;
 
-        Ok(())
+        Ok(chunk)
     }
 
-    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
-    where
-        I: IntoIterator<Item = Self::Color>,
-    {
-        let mut colors = colors.into_iter();
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        for byte in bytes {
+            self.crc ^= *byte as u32;
 
-        for (byte_offset, bits_offset) in self.offsets(*area) {
-            if let Some(color) = colors.next() {
-                self.set(byte_offset, bits_offset, color);
+            for _ in 0..8 {
+                self.crc = (self.crc >> 1) ^ (0xEDB8_8320 & (self.crc & 1).wrapping_neg());
             }
         }
 
-        Ok(())
+        self.out.write_all(bytes)
     }
 
-    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
-        for (byte_offset, bits_offset) in self.offsets(*area) {
-            self.set(byte_offset, bits_offset, color);
-        }
+    fn end(self) -> std::io::Result<()> {
+        self.out.write_all(&(!self.crc).to_be_bytes())
+    }
+}
 
-        Ok(())
+#[cfg(feature = "image-export")]
+struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+#[cfg(feature = "image-export")]
+impl Adler32 {
+    const MOD: u32 = 65521;
+
+    fn new() -> Self {
+        Self { a: 1, b: 0 }
     }
 
-    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
-        if Self::to_bits(color) == 0 {
-            for byte in self.buf.iter_mut() {
-                *byte = 0;
-            }
-        } else {
-            for (byte_offset, bits_offset) in self.offsets(self.bounding_box()) {
-                self.set(byte_offset, bits_offset, color);
-            }
+    fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.a = (self.a + *byte as u32) % Self::MOD;
+            self.b = (self.b + self.a) % Self::MOD;
         }
+    }
 
-        Ok(())
+    fn finish(&self) -> u32 {
+        self.b << 16 | self.a
     }
 }
 
 //
-// Rotated
+// Terminal
 //
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
-pub enum RotateAngle {
-    Degrees90,
-    Degrees180,
-    Degrees270,
+#[cfg(feature = "terminal")]
+mod terminal;
+
+#[cfg(feature = "terminal")]
+pub use terminal::{TerminalCell, TerminalColor, TerminalDisplay};
+
+//
+// Recorder
+//
+
+/// A drawing call as received by a [`Recorder`].
+///
+/// Display lists only live in memory, a serialised format to carry them from a device to the
+/// simulator is out of scope for now.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum DrawCall<C>
+where
+    C: PixelColor,
+{
+    DrawIter(Vec<Pixel<C>>),
+    FillContiguous(Rectangle, Vec<C>),
+    FillSolid(Rectangle, C),
+    /// Clears the whole target, which covered the rectangle when the call was recorded.
+    Clear(Rectangle, C),
+    Flush,
 }
 
-impl RotateAngle {
-    fn transform(&self, point: Point, pdim: &Rectangle) -> Point {
+impl<C> DrawCall<C>
+where
+    C: PixelColor,
+{
+    /// The number of pixels the call covers, the area for fills.
+    pub fn pixels(&self) -> usize {
         match self {
-            RotateAngle::Degrees90 => Point::new(
-                pdim.top_left.x + pdim.size.width as i32 - point.y,
-                pdim.top_left.y + point.x,
-            ),
-            RotateAngle::Degrees180 => Point::new(
-                pdim.top_left.x + pdim.size.height as i32 - point.x,
-                pdim.top_left.y + pdim.size.width as i32 - point.y,
-            ),
-            RotateAngle::Degrees270 => Point::new(
-                pdim.top_left.x + point.y,
-                pdim.top_left.y + pdim.size.height as i32 - point.x,
-            ),
+            Self::DrawIter(pixels) => pixels.len(),
+            Self::FillContiguous(area, _) | Self::FillSolid(area, _) | Self::Clear(area, _) => {
+                area.size.width as usize * area.size.height as usize
+            }
+            Self::Flush => 0,
         }
     }
 
-    fn transform_size(&self, size: Size) -> Size {
-        if *self != RotateAngle::Degrees180 {
-            Size::new(size.height, size.width)
-        } else {
-            size
+    /// Draws the call onto `target`, flushes are left to the caller.
+    pub fn draw<T>(&self, target: &mut T) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Color = C>,
+    {
+        match self {
+            Self::DrawIter(pixels) => target.draw_iter(pixels.iter().copied()),
+            Self::FillContiguous(area, colors) => {
+                target.fill_contiguous(area, colors.iter().copied())
+            }
+            Self::FillSolid(area, color) => target.fill_solid(area, *color),
+            Self::Clear(_, color) => target.clear(*color),
+            Self::Flush => Ok(()),
         }
     }
+}
 
-    fn transform_rect(&self, rect: &Rectangle, pdim: &Rectangle) -> Rectangle {
-        let point1 = self.transform(rect.top_left, pdim);
-        let point2 = self.transform(rect.top_left + rect.size, pdim);
-
-        let x1 = min(point1.x, point2.x);
-        let y1 = min(point1.y, point2.y);
-
-        let x2 = max(point1.x, point2.x);
-        let y2 = max(point1.y, point2.y);
+/// Totals over a display list. Comparing both shows how much of the traffic is per pixel.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Debug)]
+pub struct DrawStats {
+    pub calls: usize,
+    pub pixels: usize,
+}
 
-        Rectangle::with_corners(Point::new(x1, y1), Point::new(x2, y2))
+impl DrawStats {
+    pub fn of<'a, C, I>(calls: I) -> Self
+    where
+        C: PixelColor + 'a,
+        I: IntoIterator<Item = &'a DrawCall<C>>,
+    {
+        calls.into_iter().fold(Self::default(), |stats, call| Self {
+            calls: stats.calls + 1,
+            pixels: stats.pixels + call.pixels(),
+        })
     }
 }
 
-pub struct Rotated<'a, T>
+/// Logs every call made to the parent into a display list, which can be played back onto
+/// another target with [`replay`].
+///
+/// Placed below an adapter, the list shows what the adapter turns each call into.
+pub struct Recorder<'a, T>
 where
     T: DrawTarget,
 {
     parent: &'a mut T,
-    angle: RotateAngle,
+    calls: &'a mut Vec<DrawCall<T::Color>>,
 }
 
-impl<'a, T> Rotated<'a, T>
+impl<'a, T> Recorder<'a, T>
 where
     T: DrawTarget,
 {
-    pub fn new(parent: &'a mut T, angle: RotateAngle) -> Self {
-        Self { parent, angle }
+    /// New calls are appended to `calls`.
+    pub fn new(parent: &'a mut T, calls: &'a mut Vec<DrawCall<T::Color>>) -> Self {
+        Self { parent, calls }
     }
 }
 
-impl<'a, T> DrawTarget for Rotated<'a, T>
+impl<'a, T> DrawTarget for Recorder<'a, T>
 where
     T: DrawTarget,
 {
-    type Error = T::Error;
     type Color = T::Color;
+    type Error = T::Error;
 
     fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        let pdim = self.parent.bounding_box();
-        let angle = self.angle;
+        let pixels: Vec<_> = pixels.into_iter().collect();
+        let result = self.parent.draw_iter(pixels.iter().copied());
 
-        self.parent.draw_iter(
-            pixels
-                .into_iter()
-                .map(|pixel| Pixel(angle.transform(pixel.0, &pdim), pixel.1)),
-        )
+        self.calls.push(DrawCall::DrawIter(pixels));
+
+        result
     }
 
     fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
     where
         I: IntoIterator<Item = Self::Color>,
     {
-        let pdim = self.parent.bounding_box();
-        let angle = self.angle;
+        let len = area.size.width as usize * area.size.height as usize;
+        let colors: Vec<_> = colors.into_iter().take(len).collect();
+        let result = self.parent.fill_contiguous(area, colors.iter().copied());
 
-        self.parent.draw_iter(
-            area.points()
-                .zip(colors)
-                .map(|(pos, color)| Pixel(angle.transform(pos, &pdim), color)),
-        )
+        self.calls.push(DrawCall::FillContiguous(*area, colors));
+
+        result
     }
 
     fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
-        let pdim = self.parent.bounding_box();
-        let angle = self.angle;
+        self.calls.push(DrawCall::FillSolid(*area, color));
 
-        self.parent
-            .fill_solid(&angle.transform_rect(area, &pdim), color)
+        self.parent.fill_solid(area, color)
     }
 
     fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.calls
+            .push(DrawCall::Clear(self.parent.bounding_box(), color));
+
         self.parent.clear(color)
     }
 }
 
-impl<'a, T> OriginDimensions for Rotated<'a, T>
+impl<'a, T> Dimensions for Recorder<'a, T>
 where
     T: DrawTarget,
 {
-    fn size(&self) -> Size {
-        let bbox = self.parent.bounding_box();
-
-        self.angle.transform_size(bbox.size)
+    fn bounding_box(&self) -> Rectangle {
+        self.parent.bounding_box()
     }
 }
 
-//
-// Scaled
-//
-
-pub struct Scaled<'a, T>
+impl<'a, T> Flushable for Recorder<'a, T>
 where
-    T: DrawTarget,
+    T: Flushable,
 {
-    parent: &'a mut T,
-    size: Size,
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.calls.push(DrawCall::Flush);
+
+        self.parent.flush()
+    }
 }
 
-impl<'a, T> Scaled<'a, T>
+impl<'a, T> ReadableTarget for Recorder<'a, T>
 where
-    T: DrawTarget,
+    T: ReadableTarget,
 {
-    pub fn new(parent: &'a mut T, size: Size) -> Self {
-        Self { parent, size }
-    }
-
-    fn transform(point: Point, size: Size, pdim: &Rectangle) -> Point {
-        Point::new(
-            pdim.top_left.x + point.x * size.width as i32 / pdim.size.width as i32,
-            pdim.top_left.y + point.y * size.height as i32 / pdim.size.height as i32,
-        )
+    fn get_pixel(&self, point: Point) -> Option<Self::Color> {
+        self.parent.get_pixel(point)
     }
 }
 
-impl<'a, T> DrawTarget for Scaled<'a, T>
+pub struct RecorderT<T>(T, Vec<DrawCall<T::Color>>)
+where
+    T: DrawTarget;
+
+impl<T> Transformer for RecorderT<T>
 where
     T: DrawTarget,
 {
-    type Error = T::Error;
     type Color = T::Color;
+    type Error = T::Error;
 
-    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
-    where
-        I: IntoIterator<Item = Pixel<Self::Color>>,
-    {
-        let pdim = self.parent.bounding_box();
-        let size = self.size;
+    type DrawTarget<'a> = Recorder<'a, T> where Self: 'a;
 
-        self.parent.draw_iter(
-            pixels
-                .into_iter()
-                .map(|pixel| Pixel(Self::transform(pixel.0, size, &pdim), pixel.1)),
-        )
+    fn transform<'a>(&'a mut self) -> Self::DrawTarget<'a> {
+        self.0.recorder(&mut self.1)
     }
 
-    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
-    where
-        I: IntoIterator<Item = Self::Color>,
-    {
-        let pdim = self.parent.bounding_box();
-        let size = self.size;
+    fn bounding_box(&self) -> Option<Rectangle> {
+        Some(self.0.bounding_box())
+    }
+}
 
-        self.parent.draw_iter(
-            area.points()
-                .zip(colors)
-                .map(|(pos, color)| Pixel(Self::transform(pos, size, &pdim), color)),
-        )
+impl<T> ReadableTransformer for RecorderT<T>
+where
+    T: ReadableTarget,
+{
+    fn get_pixel(&self, point: Point) -> Option<Self::Color> {
+        self.0.get_pixel(point)
     }
+}
 
-    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
-        let area = Rectangle::new(area.top_left, self.size);
+inner_accessors!(RecorderT<T> where T: DrawTarget);
 
-        self.parent.fill_solid(&area, color)
+impl<T> RecorderT<T>
+where
+    T: DrawTarget,
+{
+    pub fn calls(&self) -> &[DrawCall<T::Color>] {
+        &self.1
     }
 
-    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
-        self.parent.clear(color)
+    /// Returns the calls recorded so far and starts a new list.
+    pub fn take_calls(&mut self) -> Vec<DrawCall<T::Color>> {
+        core::mem::take(&mut self.1)
+    }
+
+    pub fn stats(&self) -> DrawStats {
+        DrawStats::of(&self.1)
     }
 }
 
-impl<'a, T> OriginDimensions for Scaled<'a, T>
+/// Plays a recorded display list onto any target, recorded flushes are skipped.
+pub fn replay<'a, T, I>(calls: I, target: &mut T) -> Result<(), T::Error>
 where
     T: DrawTarget,
+    T::Color: 'a,
+    I: IntoIterator<Item = &'a DrawCall<T::Color>>,
 {
-    fn size(&self) -> Size {
-        self.size
+    for call in calls {
+        call.draw(target)?;
+    }
+
+    Ok(())
+}
+
+/// Like [`replay`], but also flushes `target` wherever the recording did.
+pub fn replay_flushing<'a, T, I>(calls: I, target: &mut T) -> Result<(), T::Error>
+where
+    T: Flushable,
+    T::Color: 'a,
+    I: IntoIterator<Item = &'a DrawCall<T::Color>>,
+{
+    for call in calls {
+        match call {
+            DrawCall::Flush => target.flush()?,
+            call => call.draw(target)?,
+        }
     }
+
+    Ok(())
 }
 
 //
@@ -866,14 +4749,31 @@ where
 pub trait DrawTargetExt2: DrawTarget + Sized {
     fn rotated(&mut self, angle: RotateAngle) -> Rotated<'_, Self>;
 
+    fn oriented(&mut self, orientation: Orientation) -> Oriented<'_, Self>;
+
     fn scaled(&mut self, size: Size) -> Scaled<'_, Self>;
 
+    fn scaled_with_mode(&mut self, size: Size, mode: ScaleMode<Self::Color>) -> Scaled<'_, Self>;
+
+    fn affine(&mut self, matrix: AffineMatrix) -> Affine<'_, Self>;
+
+    fn tee<'a, B>(&'a mut self, other: &'a mut B) -> Tee<'a, Self, B>
+    where
+        B: DrawTarget<Color = Self::Color>;
+
+    fn blended(&mut self) -> Blended<'_, Self>
+    where
+        Self: ReadableTarget,
+        Self::Color: Blend;
+
     fn flushing<F: FnMut(&mut Self) -> Result<(), Self::Error>>(
         &mut self,
         flusher: F,
     ) -> Flushing<'_, Self, F>;
 
-    fn noop_flushing(&mut self) -> Flushing<'_, Self, fn(&mut Self) -> Result<(), Self::Error>>;
+    fn noop_flushing(&mut self) -> Flushing<'_, Self, NoopFlusher<Self>>;
+
+    fn recorder<'a>(&'a mut self, calls: &'a mut Vec<DrawCall<Self::Color>>) -> Recorder<'a, Self>;
 }
 
 impl<T> DrawTargetExt2 for T
@@ -884,10 +4784,37 @@ where
         Rotated::new(self, angle)
     }
 
+    fn oriented(&mut self, orientation: Orientation) -> Oriented<'_, Self> {
+        Oriented::new(self, orientation)
+    }
+
     fn scaled(&mut self, size: Size) -> Scaled<'_, Self> {
         Scaled::new(self, size)
     }
 
+    fn scaled_with_mode(&mut self, size: Size, mode: ScaleMode<Self::Color>) -> Scaled<'_, Self> {
+        Scaled::with_mode(self, size, mode)
+    }
+
+    fn affine(&mut self, matrix: AffineMatrix) -> Affine<'_, Self> {
+        Affine::new(self, matrix)
+    }
+
+    fn tee<'a, B>(&'a mut self, other: &'a mut B) -> Tee<'a, Self, B>
+    where
+        B: DrawTarget<Color = Self::Color>,
+    {
+        Tee::new(self, other)
+    }
+
+    fn blended(&mut self) -> Blended<'_, Self>
+    where
+        Self: ReadableTarget,
+        Self::Color: Blend,
+    {
+        Blended::new(self)
+    }
+
     fn flushing<F: FnMut(&mut Self) -> Result<(), Self::Error>>(
         &mut self,
         flusher: F,
@@ -895,9 +4822,13 @@ where
         Flushing::new(self, flusher)
     }
 
-    fn noop_flushing(&mut self) -> Flushing<'_, Self, fn(&mut Self) -> Result<(), Self::Error>> {
+    fn noop_flushing(&mut self) -> Flushing<'_, Self, NoopFlusher<Self>> {
         Flushing::noop(self)
     }
+
+    fn recorder<'a>(&'a mut self, calls: &'a mut Vec<DrawCall<Self::Color>>) -> Recorder<'a, Self> {
+        Recorder::new(self, calls)
+    }
 }
 
 pub trait OwnedDrawTargetExt: DrawTarget + Sized {
@@ -913,8 +4844,29 @@ pub trait OwnedDrawTargetExt: DrawTarget + Sized {
 
     fn owned_rotated(self, angle: RotateAngle) -> Owned<RotatedT<Self>>;
 
+    fn owned_oriented(self, orientation: Orientation) -> Owned<OrientedT<Self>>;
+
     fn owned_scaled(self, size: Size) -> Owned<ScaledT<Self>>;
 
+    fn owned_scaled_with_mode(
+        self,
+        size: Size,
+        mode: ScaleMode<Self::Color>,
+    ) -> Owned<ScaledT<Self>>;
+
+    fn owned_affine(self, matrix: AffineMatrix) -> Owned<AffineT<Self>>;
+
+    fn owned_tee<B>(self, other: B) -> Owned<TeeT<Self, B>>
+    where
+        B: DrawTarget<Color = Self::Color>;
+
+    fn owned_blended(self) -> Owned<BlendedT<Self>>
+    where
+        Self: ReadableTarget,
+        Self::Color: Blend;
+
+    fn pipeline(self) -> PipelineBuilder<Self, NoopFlusher<Self>>;
+
     fn owned_flushing<F: FnMut(&mut Self) -> Result<(), Self::Error> + Send + Clone + 'static>(
         self,
         flusher: F,
@@ -923,20 +4875,34 @@ pub trait OwnedDrawTargetExt: DrawTarget + Sized {
         Self: 'static,
         Self::Error: 'static;
 
-    fn owned_noop_flushing(
-        self,
-    ) -> Owned<FlushingT<Self, fn(&mut Self) -> Result<(), Self::Error>>>
+    fn owned_noop_flushing(self) -> Owned<FlushingT<Self, NoopFlusher<Self>>>
     where
         Self: 'static,
         Self::Error: 'static;
 
+    /// Panics like [`Buffered::new`] if either buffer is too small.
     fn owned_buffered<'a>(
         self,
         draw_buf: &'a mut [u8],
         reference_buf: &'a mut [u8],
-    ) -> Buffered<'a, Self>
+    ) -> Buffered<Self, &'a mut [u8]>
+    where
+        Self::Color: PackedColor;
+
+    /// Fails if either buffer is smaller than [`buffer_size`] of the display.
+    fn try_owned_buffered<'a>(
+        self,
+        draw_buf: &'a mut [u8],
+        reference_buf: &'a mut [u8],
+    ) -> Result<Buffered<Self, &'a mut [u8]>, BufferedError<Self::Error>>
+    where
+        Self::Color: PackedColor;
+
+    fn owned_alloc_buffered(self) -> OwnedBuffered<Self>
     where
-        Self::Color: PixelColor + IntoStorage<Storage = u8> + From<u8>;
+        Self::Color: PackedColor;
+
+    fn owned_recorder(self) -> Owned<RecorderT<Self>>;
 }
 
 impl<T> OwnedDrawTargetExt for T
@@ -966,8 +4932,43 @@ where
         RotatedT(self, angle).into_owned()
     }
 
+    fn owned_oriented(self, orientation: Orientation) -> Owned<OrientedT<Self>> {
+        OrientedT(self, orientation).into_owned()
+    }
+
     fn owned_scaled(self, size: Size) -> Owned<ScaledT<Self>> {
-        ScaledT(self, size).into_owned()
+        self.owned_scaled_with_mode(size, ScaleMode::Nearest)
+    }
+
+    fn owned_scaled_with_mode(
+        self,
+        size: Size,
+        mode: ScaleMode<Self::Color>,
+    ) -> Owned<ScaledT<Self>> {
+        ScaledT(self, size, mode).into_owned()
+    }
+
+    fn owned_affine(self, matrix: AffineMatrix) -> Owned<AffineT<Self>> {
+        AffineT(self, matrix).into_owned()
+    }
+
+    fn owned_tee<B>(self, other: B) -> Owned<TeeT<Self, B>>
+    where
+        B: DrawTarget<Color = Self::Color>,
+    {
+        TeeT(self, other).into_owned()
+    }
+
+    fn owned_blended(self) -> Owned<BlendedT<Self>>
+    where
+        Self: ReadableTarget,
+        Self::Color: Blend,
+    {
+        BlendedT(self).into_owned()
+    }
+
+    fn pipeline(self) -> PipelineBuilder<Self, NoopFlusher<Self>> {
+        PipelineBuilder::new(self)
     }
 
     fn owned_flushing<F: FnMut(&mut Self) -> Result<(), Self::Error> + Send + Clone + 'static>(
@@ -981,7 +4982,7 @@ where
         FlushingT(self, flusher).into_owned()
     }
 
-    fn owned_noop_flushing(self) -> Owned<FlushingT<Self, fn(&mut Self) -> Result<(), Self::Error>>>
+    fn owned_noop_flushing(self) -> Owned<FlushingT<Self, NoopFlusher<Self>>>
     where
         Self: 'static,
         Self::Error: 'static,
@@ -993,10 +4994,35 @@ where
         self,
         draw_buf: &'a mut [u8],
         reference_buf: &'a mut [u8],
-    ) -> Buffered<'a, Self>
+    ) -> Buffered<Self, &'a mut [u8]>
     where
-        Self::Color: PixelColor + IntoStorage<Storage = u8> + From<u8>,
+        Self::Color: PackedColor,
     {
         Buffered::new(draw_buf, reference_buf, self)
     }
+
+    fn try_owned_buffered<'a>(
+        self,
+        draw_buf: &'a mut [u8],
+        reference_buf: &'a mut [u8],
+    ) -> Result<Buffered<Self, &'a mut [u8]>, BufferedError<Self::Error>>
+    where
+        Self::Color: PackedColor,
+    {
+        Buffered::try_new(draw_buf, reference_buf, self)
+    }
+
+    fn owned_alloc_buffered(self) -> OwnedBuffered<Self>
+    where
+        Self::Color: PackedColor,
+    {
+        Buffered::new_alloc(self)
+    }
+
+    fn owned_recorder(self) -> Owned<RecorderT<Self>> {
+        RecorderT(self, Vec::new()).into_owned()
+    }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,322 @@
+//! A [`TerminalDisplay`] printing frames with half-block characters, for development without
+//! hardware.
+
+use core::ops::Range;
+use std::vec::Vec;
+
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::pixelcolor::{
+    Bgr555, Bgr565, Bgr666, Bgr888, BinaryColor, Gray2, Gray4, Gray8, GrayColor, Rgb555, Rgb565,
+    Rgb666, Rgb888, RgbColor,
+};
+use embedded_graphics::prelude::{Dimensions, OriginDimensions, PixelColor, Point, Size};
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::Pixel;
+
+use super::{Flushable, ReadableTarget};
+
+/// What a [`TerminalDisplay`] prints for one character cell, which covers two pixel rows.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum TerminalCell {
+    /// A character in the terminal's own colours.
+    Glyph(char),
+    /// An upper half block, the top pixel is the foreground and the bottom pixel the background.
+    HalfBlock { top: Rgb888, bottom: Rgb888 },
+}
+
+/// Colours a [`TerminalDisplay`] knows how to print.
+pub trait TerminalColor: PixelColor {
+    fn cell(top: Self, bottom: Self) -> TerminalCell;
+}
+
+impl TerminalColor for BinaryColor {
+    fn cell(top: Self, bottom: Self) -> TerminalCell {
+        TerminalCell::Glyph(match (top.is_on(), bottom.is_on()) {
+            (false, false) => ' ',
+            (true, false) => '▀',
+            (false, true) => '▄',
+            (true, true) => '█',
+        })
+    }
+}
+
+/// Shade characters from black to white.
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// Gray cells are printed as the shade closest to the average of both pixels.
+macro_rules! impl_terminal_gray {
+    ($($color:ty),*) => {$(
+        impl TerminalColor for $color {
+            fn cell(top: Self, bottom: Self) -> TerminalCell {
+                let sum = top.luma() as usize + bottom.luma() as usize;
+                let max = 2 * Self::WHITE.luma() as usize;
+
+                TerminalCell::Glyph(SHADES[(sum * (SHADES.len() - 1) + max / 2) / max])
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_terminal_rgb {
+    ($($color:ty),*) => {$(
+        impl TerminalColor for $color {
+            fn cell(top: Self, bottom: Self) -> TerminalCell {
+                TerminalCell::HalfBlock {
+                    top: top.into(),
+                    bottom: bottom.into(),
+                }
+            }
+        }
+    )*};
+}
+
+impl_terminal_gray!(Gray2, Gray4, Gray8);
+impl_terminal_rgb!(Rgb555, Bgr555, Rgb565, Bgr565, Rgb666, Bgr666, Rgb888, Bgr888);
+
+/// A framebuffer that is printed to a terminal on every flush, for development without hardware.
+///
+/// Each character cell shows two pixels stacked on top of each other, RGB colours use 24-bit ANSI
+/// escape sequences. With diffing enabled a frame is printed over the previous one, and only the
+/// cell rows that changed since then are written again.
+pub struct TerminalDisplay<C, W> {
+    size: Size,
+    background: C,
+    pixels: Vec<C>,
+    shown: Option<Vec<C>>,
+    diffing: bool,
+    out: W,
+}
+
+impl<C> TerminalDisplay<C, std::io::Stdout>
+where
+    C: TerminalColor,
+{
+    pub fn new(size: Size, background: C) -> Self {
+        Self::with_writer(size, background, std::io::stdout())
+    }
+}
+
+impl<C, W> TerminalDisplay<C, W>
+where
+    C: TerminalColor,
+{
+    pub fn with_writer(size: Size, background: C, out: W) -> Self {
+        Self {
+            size,
+            background,
+            pixels: vec![background; size.width as usize * size.height as usize],
+            shown: None,
+            diffing: false,
+            out,
+        }
+    }
+
+    pub fn with_diffing(mut self, diffing: bool) -> Self {
+        self.set_diffing(diffing);
+        self
+    }
+
+    pub fn diffing(&self) -> bool {
+        self.diffing
+    }
+
+    pub fn set_diffing(&mut self, diffing: bool) {
+        self.diffing = diffing;
+        self.shown = None;
+    }
+
+    /// Prints the whole display below the cursor on the next flush, e.g. after something else
+    /// was printed over the previous frame.
+    pub fn invalidate(&mut self) {
+        self.shown = None;
+    }
+
+    pub fn writer(&self) -> &W {
+        &self.out
+    }
+
+    pub fn writer_mut(&mut self) -> &mut W {
+        &mut self.out
+    }
+
+    pub fn into_writer(self) -> W {
+        self.out
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        self.bounding_box()
+            .contains(point)
+            .then(|| point.y as usize * self.size.width as usize + point.x as usize)
+    }
+
+    /// The pixels shown in cell row `row`.
+    fn cell_row(&self, row: usize) -> Range<usize> {
+        let width = self.size.width as usize;
+
+        row * 2 * width..((row + 1) * 2 * width).min(self.pixels.len())
+    }
+}
+
+impl<C, W> TerminalDisplay<C, W>
+where
+    C: TerminalColor,
+    W: std::io::Write,
+{
+    fn write_row(&mut self, row: usize) -> std::io::Result<()> {
+        let width = self.size.width as usize;
+        let pixels = &self.pixels[self.cell_row(row)];
+        let (top, bottom) = pixels.split_at(width);
+
+        // Escape sequences are only written when the colours change
+        let mut colors = None;
+
+        for (x, top) in top.iter().enumerate() {
+            let bottom = bottom.get(x).copied().unwrap_or(self.background);
+
+            match C::cell(*top, bottom) {
+                TerminalCell::Glyph(glyph) => {
+                    if colors.take().is_some() {
+                        write!(self.out, "\x1b[0m")?;
+                    }
+
+                    write!(self.out, "{}", glyph)?;
+                }
+                TerminalCell::HalfBlock { top, bottom } => {
+                    if colors != Some((top, bottom)) {
+                        write!(
+                            self.out,
+                            "\x1b[38;2;{};{};{};48;2;{};{};{}m",
+                            top.r(),
+                            top.g(),
+                            top.b(),
+                            bottom.r(),
+                            bottom.g(),
+                            bottom.b()
+                        )?;
+                        colors = Some((top, bottom));
+                    }
+
+                    write!(self.out, "▀")?;
+                }
+            }
+        }
+
+        if colors.is_some() {
+            write!(self.out, "\x1b[0m")?;
+        }
+
+        writeln!(self.out)
+    }
+}
+
+impl<C, W> DrawTarget for TerminalDisplay<C, W>
+where
+    C: TerminalColor,
+    W: std::io::Write,
+{
+    type Color = C;
+    type Error = std::io::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let Some(index) = self.index(point) {
+                self.pixels[index] = color;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounding_box());
+        let width = self.size.width as usize;
+
+        if let Some(bottom_right) = area.bottom_right() {
+            for y in area.top_left.y..=bottom_right.y {
+                let start = y as usize * width;
+
+                self.pixels[start + area.top_left.x as usize..=start + bottom_right.x as usize]
+                    .fill(color);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.pixels.fill(color);
+
+        Ok(())
+    }
+}
+
+impl<C, W> OriginDimensions for TerminalDisplay<C, W> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<C, W> Flushable for TerminalDisplay<C, W>
+where
+    C: TerminalColor,
+    W: std::io::Write,
+{
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        let rows = (self.size.height as usize + 1) / 2;
+        let shown = self.shown.take();
+
+        // Go back to the first line of the previous frame
+        if shown.is_some() && rows > 0 {
+            write!(self.out, "\x1b[{}F", rows)?;
+        }
+
+        let mut skipped = 0;
+
+        for row in 0..rows {
+            let range = self.cell_row(row);
+
+            if let Some(shown) = &shown {
+                if shown[range.clone()] == self.pixels[range] {
+                    skipped += 1;
+                    continue;
+                }
+            }
+
+            if skipped > 0 {
+                write!(self.out, "\x1b[{}E", skipped)?;
+                skipped = 0;
+            }
+
+            self.write_row(row)?;
+        }
+
+        if skipped > 0 {
+            write!(self.out, "\x1b[{}E", skipped)?;
+        }
+
+        if self.diffing {
+            self.shown = match shown {
+                Some(mut shown) => {
+                    shown.copy_from_slice(&self.pixels);
+                    Some(shown)
+                }
+                None => Some(self.pixels.clone()),
+            };
+        }
+
+        self.out.flush()
+    }
+}
+
+impl<C, W> ReadableTarget for TerminalDisplay<C, W>
+where
+    C: TerminalColor,
+    W: std::io::Write,
+{
+    fn get_pixel(&self, point: Point) -> Option<Self::Color> {
+        self.index(point).map(|index| self.pixels[index])
+    }
+}
@@ -0,0 +1,2186 @@
+use embedded_graphics::pixelcolor::{BinaryColor, Gray2, Gray4, Gray8, Rgb565, Rgb888, RgbColor};
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+
+use super::*;
+#[cfg(feature = "image-export")]
+use crate::test_support::assert_png_snapshot;
+use crate::test_support::{assert_snapshot, to_ascii, Op, RecordingDisplay};
+
+fn display(width: u32, height: u32) -> RecordingDisplay<BinaryColor> {
+    RecordingDisplay::new(Size::new(width, height), BinaryColor::Off)
+}
+
+fn gray_display(width: u32, height: u32) -> RecordingDisplay<Gray8> {
+    RecordingDisplay::new(Size::new(width, height), Gray8::BLACK)
+}
+
+/// An "F" filling the logical area, so that every orientation looks different.
+fn draw_f<D>(target: &mut D)
+where
+    D: DrawTarget<Color = BinaryColor>,
+    D::Error: Debug,
+{
+    let bbox = target.bounding_box();
+    let (top_left, size) = (bbox.top_left, bbox.size);
+
+    target
+        .fill_solid(
+            &Rectangle::new(top_left, Size::new(size.width, 1)),
+            BinaryColor::On,
+        )
+        .unwrap();
+    target
+        .fill_solid(
+            &Rectangle::new(top_left, Size::new(1, size.height)),
+            BinaryColor::On,
+        )
+        .unwrap();
+    target
+        .draw_iter((1..size.width as i32 / 2 + 1).map(|x| {
+            Pixel(
+                top_left + Point::new(x, size.height as i32 / 2),
+                BinaryColor::On,
+            )
+        }))
+        .unwrap();
+}
+
+/// A horizontal gradient, one grey level per column.
+fn draw_gradient<D>(target: &mut D)
+where
+    D: DrawTarget<Color = Gray8>,
+    D::Error: Debug,
+{
+    let bbox = target.bounding_box();
+    let colors = bbox.points().map(|point| Gray8::new((point.x * 32) as u8));
+
+    target.fill_contiguous(&bbox, colors).unwrap();
+}
+
+//
+// Oriented
+//
+
+#[test]
+fn oriented_snapshots() {
+    for orientation in Orientation::ALL {
+        let mut display = display(5, 3);
+        draw_f(&mut display.oriented(orientation));
+
+        assert_snapshot!(
+            format!("oriented_{:?}", orientation).to_lowercase(),
+            &display
+        );
+    }
+}
+
+#[test]
+fn rotated_matches_oriented() {
+    for angle in [
+        RotateAngle::Degrees90,
+        RotateAngle::Degrees180,
+        RotateAngle::Degrees270,
+    ] {
+        let (mut rotated, mut oriented) = (display(5, 3), display(5, 3));
+
+        draw_f(&mut rotated.rotated(angle));
+        draw_f(&mut oriented.oriented(angle.into()));
+
+        assert_eq!(to_ascii(&rotated), to_ascii(&oriented), "{:?}", angle);
+    }
+}
+
+#[test]
+fn orientation_is_a_bijection() {
+    let parent = Rectangle::new(Point::new(2, 1), Size::new(4, 3));
+
+    for orientation in Orientation::ALL {
+        let logical = Rectangle::new(Point::zero(), orientation.transform_size(parent.size));
+        let mut mapped: Vec<Point> = logical
+            .points()
+            .map(|point| orientation.transform(point, &parent))
+            .collect();
+
+        assert!(mapped.iter().all(|point| parent.contains(*point)));
+
+        mapped.sort_by_key(|point| (point.y, point.x));
+        mapped.dedup();
+        assert_eq!(mapped.len(), 12, "{:?}", orientation);
+    }
+}
+
+#[test]
+fn orientation_inverse_undoes_it() {
+    for size in [
+        Size::new(1, 1),
+        Size::new(3, 3),
+        Size::new(4, 3),
+        Size::new(2, 5),
+    ] {
+        let parent = Rectangle::new(Point::new(-2, 3), size);
+
+        for orientation in Orientation::ALL {
+            let logical = Rectangle::new(Point::zero(), orientation.transform_size(size));
+            let inverse = orientation.inverse();
+
+            assert_eq!(inverse.inverse(), orientation);
+            assert_eq!(inverse.transform_size(logical.size), size);
+
+            for point in logical.points() {
+                let mapped = orientation.transform(point, &parent) - parent.top_left;
+
+                assert_eq!(
+                    inverse.transform(mapped, &logical),
+                    point,
+                    "{:?} {:?} {:?}",
+                    orientation,
+                    size,
+                    point
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn orientations_are_distinct() {
+    let parent = Rectangle::new(Point::zero(), Size::new(3, 3));
+    let mut mappings: Vec<Vec<Point>> = Orientation::ALL
+        .iter()
+        .map(|orientation| {
+            parent
+                .points()
+                .map(|point| orientation.transform(point, &parent))
+                .collect()
+        })
+        .collect();
+
+    mappings.sort_by_key(|points| {
+        points
+            .iter()
+            .map(|point| (point.x, point.y))
+            .collect::<Vec<_>>()
+    });
+    mappings.dedup();
+    assert_eq!(mappings.len(), 8);
+}
+
+#[test]
+fn orientation_maps_every_rectangle_onto_its_bounding_box() {
+    let parent = Rectangle::new(Point::new(1, -1), Size::new(4, 3));
+
+    for orientation in Orientation::ALL {
+        let logical = Rectangle::new(Point::zero(), orientation.transform_size(parent.size));
+
+        for top_left in logical.points() {
+            let bottom_right = logical.bottom_right().unwrap();
+            let sizes = Rectangle::with_corners(top_left, bottom_right)
+                .points()
+                .map(|corner| {
+                    Size::new(
+                        (corner.x - top_left.x + 1) as u32,
+                        (corner.y - top_left.y + 1) as u32,
+                    )
+                });
+
+            for size in sizes.chain([Size::zero()]) {
+                let rect = Rectangle::new(top_left, size);
+                let mapped = orientation.transform_rect(&rect, &parent);
+
+                if size == Size::zero() {
+                    assert!(mapped.is_zero_sized());
+                    continue;
+                }
+
+                let corners = rect
+                    .points()
+                    .map(|point| orientation.transform(point, &parent));
+                let (min, max) = corners.fold(
+                    (
+                        Point::new(i32::MAX, i32::MAX),
+                        Point::new(i32::MIN, i32::MIN),
+                    ),
+                    |(min, max), point| (min.component_min(point), max.component_max(point)),
+                );
+
+                assert_eq!(
+                    mapped,
+                    Rectangle::with_corners(min, max),
+                    "{:?} {:?}",
+                    orientation,
+                    rect
+                );
+                assert_eq!(mapped.size, orientation.transform_size(size));
+            }
+        }
+
+        let mut display = display(4, 3);
+        assert_eq!(display.oriented(orientation).bounding_box(), logical);
+    }
+}
+
+#[test]
+fn oriented_reads_back_what_it_drew() {
+    for orientation in Orientation::ALL {
+        let mut display = gray_display(5, 3);
+        let mut oriented = display.oriented(orientation);
+
+        draw_gradient(&mut oriented);
+
+        for point in oriented.bounding_box().points() {
+            assert_eq!(
+                oriented.get_pixel(point),
+                Some(Gray8::new((point.x * 32) as u8)),
+                "{:?} {:?}",
+                orientation,
+                point
+            );
+        }
+    }
+}
+
+#[test]
+fn owned_oriented_reads_back_through_shared_references() {
+    for orientation in Orientation::ALL {
+        let mut owned = gray_display(5, 3).owned_oriented(orientation);
+
+        draw_gradient(&mut owned);
+
+        let owned = &owned;
+        for (y, row) in owned.rows().enumerate() {
+            let row: Vec<_> = row.collect();
+
+            assert_eq!(row.len(), owned.bounding_box().size.width as usize);
+            for (x, color) in row.into_iter().enumerate() {
+                assert_eq!(
+                    color,
+                    Some(Gray8::new((x * 32) as u8)),
+                    "{:?} {} {}",
+                    orientation,
+                    x,
+                    y
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn rows_keep_unreadable_pixels() {
+    let mut display = gray_display(3, 2);
+    draw_gradient(&mut display);
+
+    assert_eq!(
+        display.row(0).collect::<Vec<_>>(),
+        [
+            Some(Gray8::new(0)),
+            Some(Gray8::new(32)),
+            Some(Gray8::new(64))
+        ]
+    );
+    assert_eq!(display.row(2).collect::<Vec<_>>(), [None; 3]);
+
+    let mut buf = [Gray8::WHITE; 4];
+    display.read_rect(&Rectangle::new(Point::new(1, 1), Size::new(4, 1)), &mut buf);
+    assert_eq!(
+        buf,
+        [Gray8::new(32), Gray8::new(64), Gray8::WHITE, Gray8::WHITE]
+    );
+}
+
+#[test]
+fn owned_oriented_can_change_orientation() {
+    let mut owned = display(5, 3).owned_oriented(Orientation::Identity);
+    assert_eq!(owned.bounding_box().size, Size::new(5, 3));
+
+    owned.set_orientation(RotateAngle::Degrees90);
+    assert_eq!(owned.orientation(), Orientation::Rotate90);
+    assert_eq!(owned.bounding_box().size, Size::new(3, 5));
+
+    // The drawing is kept until the target is cleared
+    draw_f(&mut owned);
+    assert_eq!(to_ascii(owned.inner().inner()), "#####\n..#.#\n....#\n");
+
+    owned
+        .set_orientation_and_clear(None, BinaryColor::Off)
+        .unwrap();
+    assert_eq!(owned.bounding_box().size, Size::new(5, 3));
+    assert_eq!(to_ascii(owned.inner().inner()), ".....\n.....\n.....\n");
+}
+
+//
+// Scaled
+//
+
+#[test]
+fn scaled_snapshots() {
+    let mut up = display(6, 4);
+    draw_f(&mut up.scaled(Size::new(3, 2)));
+    assert_snapshot!("scaled_up", &up);
+
+    let mut down = display(3, 3);
+    draw_f(&mut down.scaled(Size::new(6, 6)));
+    assert_snapshot!("scaled_down", &down);
+
+    let mut nearest = gray_display(4, 2);
+    draw_gradient(&mut nearest.scaled(Size::new(8, 2)));
+    assert_snapshot!("scaled_gradient_nearest", &nearest);
+
+    let mut filtered = gray_display(4, 2);
+    draw_gradient(&mut filtered.scaled_with_mode(Size::new(8, 2), ScaleMode::box_filter()));
+    assert_snapshot!("scaled_gradient_box", &filtered);
+}
+
+#[test]
+fn scaled_up_fills_contiguous_areas_at_once() {
+    // Upscaled on both axes, and upscaled on one axis only
+    for (parent, logical) in [
+        (Size::new(8, 4), Size::new(4, 2)),
+        (Size::new(4, 4), Size::new(8, 2)),
+    ] {
+        let mut display = RecordingDisplay::new(parent, Gray8::BLACK);
+        draw_gradient(&mut display.scaled(logical));
+        assert_eq!(
+            display.ops(),
+            [Op::FillContiguous(Rectangle::new(Point::zero(), parent))]
+        );
+
+        let mut expected = RecordingDisplay::new(parent, Gray8::BLACK);
+        expected
+            .scaled(logical)
+            .draw_iter(
+                Rectangle::new(Point::zero(), logical)
+                    .points()
+                    .map(|point| Pixel(point, Gray8::new((point.x * 32) as u8))),
+            )
+            .unwrap();
+        assert_eq!(pixels(&display), pixels(&expected));
+    }
+}
+
+//
+// Affine
+//
+
+#[test]
+fn affine_orientation_matches_oriented() {
+    for orientation in Orientation::ALL {
+        let (mut affine, mut oriented) = (display(5, 3), display(5, 3));
+        let matrix = AffineMatrix::orientation(orientation, Size::new(5, 3));
+
+        draw_f(&mut affine.affine(matrix));
+        draw_f(&mut oriented.oriented(orientation));
+
+        assert_eq!(to_ascii(&affine), to_ascii(&oriented), "{:?}", orientation);
+    }
+}
+
+#[test]
+fn affine_translation_is_checked() {
+    let max = AffineMatrix::MAX_TRANSLATION;
+
+    assert_eq!(
+        AffineMatrix::checked_translation(Point::new(max, -max - 1)),
+        Some(AffineMatrix::new(
+            AffineMatrix::ONE,
+            0,
+            0,
+            AffineMatrix::ONE,
+            max * AffineMatrix::ONE,
+            (-max - 1) * AffineMatrix::ONE
+        ))
+    );
+    assert_eq!(
+        AffineMatrix::checked_translation(Point::new(max + 1, 0)),
+        None
+    );
+    assert_eq!(
+        AffineMatrix::checked_translation(Point::new(0, -max - 2)),
+        None
+    );
+    assert!(std::panic::catch_unwind(|| AffineMatrix::translation(Point::new(40_000, 0))).is_err());
+}
+
+#[test]
+fn affine_axis_aligned_fills_are_single_rectangles() {
+    let half = AffineMatrix::ONE / 2;
+    let matrices = [
+        AffineMatrix::IDENTITY,
+        AffineMatrix::translation(Point::new(-2, 3)),
+        AffineMatrix::scaling(3 * half, half),
+        AffineMatrix::scaling(AffineMatrix::fixed(2, 3), AffineMatrix::fixed(5, 2)),
+        AffineMatrix::scaling(AffineMatrix::ONE / 5, AffineMatrix::ONE / 4),
+        AffineMatrix::orientation(Orientation::Rotate180, Size::new(9, 7)),
+        AffineMatrix::scaling(-half, 3 * half).then(&AffineMatrix::new(
+            AffineMatrix::ONE,
+            0,
+            0,
+            AffineMatrix::ONE,
+            13 * half,
+            -half / 3,
+        )),
+    ];
+    let areas = [
+        Rectangle::new(Point::new(1, 1), Size::new(3, 2)),
+        Rectangle::new(Point::new(-3, 2), Size::new(20, 1)),
+        Rectangle::new(Point::new(4, 0), Size::new(1, 1)),
+        Rectangle::new(Point::new(2, 2), Size::zero()),
+    ];
+
+    for matrix in matrices {
+        for area in areas {
+            let mut solid = display(9, 7);
+            solid
+                .affine(matrix)
+                .fill_solid(&area, BinaryColor::On)
+                .unwrap();
+            assert!(solid.take_ops().len() <= 1, "{:?} {:?}", matrix, area);
+
+            let mut pixels = display(9, 7);
+            pixels
+                .affine(matrix)
+                .fill_contiguous(&area, core::iter::repeat(BinaryColor::On))
+                .unwrap();
+            assert_eq!(
+                to_ascii(&solid),
+                to_ascii(&pixels),
+                "{:?} {:?}",
+                matrix,
+                area
+            );
+        }
+    }
+}
+
+//
+// Owned
+//
+
+#[test]
+fn owned_cached_bounds_are_recomputed_once_invalidated() {
+    let mut owned = display(6, 5).owned_cropped(&Rectangle::new(Point::zero(), Size::new(2, 2)));
+    assert_eq!(owned.bounds_mode(), BoundsMode::Cached);
+    assert_eq!(owned.bounding_box().size, Size::new(2, 2));
+
+    owned
+        .0
+        .set_area(&Rectangle::new(Point::zero(), Size::new(4, 3)));
+    assert_eq!(owned.bounding_box().size, Size::new(2, 2));
+
+    owned.invalidate_bounds();
+    assert_eq!(owned.bounding_box().size, Size::new(4, 3));
+}
+
+#[test]
+fn owned_live_bounds_follow_every_change() {
+    let mut live = display(6, 5)
+        .owned_cropped(&Rectangle::new(Point::zero(), Size::new(2, 2)))
+        .with_bounds_mode(BoundsMode::Live);
+    assert_eq!(live.bounds_mode(), BoundsMode::Live);
+
+    live.0
+        .set_area(&Rectangle::new(Point::new(1, 1), Size::new(4, 3)));
+    let live = &live;
+    assert_eq!(live.bounding_box().size, Size::new(4, 3));
+}
+
+#[test]
+fn owned_bounds_follow_inner_changes() {
+    let mut owned = display(6, 5).owned_cropped(&Rectangle::new(Point::zero(), Size::new(2, 2)));
+    assert_eq!(owned.bounding_box().size, Size::new(2, 2));
+
+    owned
+        .inner_mut()
+        .set_area(&Rectangle::new(Point::zero(), Size::new(4, 3)));
+    assert_eq!(owned.bounding_box().size, Size::new(4, 3));
+}
+
+//
+// Owned adapters
+//
+
+#[test]
+fn owned_adapters_snapshots() {
+    let area = Rectangle::new(Point::new(1, 1), Size::new(4, 3));
+
+    let mut translated = display(6, 5).owned_translated(Point::new(1, 2));
+    draw_f(&mut translated);
+    assert_snapshot!("owned_translated", translated.inner().inner());
+
+    let mut cropped = display(6, 5).owned_cropped(&area);
+    draw_f(&mut cropped);
+    assert_snapshot!("owned_cropped", &cropped.into_inner().into_inner());
+
+    let mut clipped = display(6, 5).owned_clipped(&area);
+    draw_f(&mut clipped);
+    assert_snapshot!("owned_clipped", &clipped.into_inner().into_inner());
+
+    let mut rotated = display(6, 5).owned_rotated(RotateAngle::Degrees270);
+    draw_f(&mut rotated);
+    assert_snapshot!("owned_rotated", &rotated.into_inner().into_inner());
+
+    let mut scaled = display(6, 5).owned_scaled(Size::new(3, 2));
+    draw_f(&mut scaled);
+    assert_snapshot!("owned_scaled", &scaled.into_inner().into_inner());
+
+    // The sheared bounding box reaches left of the display, draw a block at the origin instead
+    let mut affine = display(6, 5).owned_affine(AffineMatrix::shearing(AffineMatrix::ONE / 2, 0));
+    affine
+        .fill_solid(
+            &Rectangle::new(Point::zero(), Size::new(3, 5)),
+            BinaryColor::On,
+        )
+        .unwrap();
+    assert_snapshot!("owned_affine", &affine.into_inner().into_inner());
+}
+
+#[test]
+fn owned_adapters_match_borrowed() {
+    let area = Rectangle::new(Point::new(1, 1), Size::new(4, 3));
+    let matrix = AffineMatrix::orientation(Orientation::Transpose, Size::new(6, 5));
+
+    let mut borrowed = display(6, 5);
+    draw_f(&mut borrowed.rotated(RotateAngle::Degrees90));
+    let mut owned = display(6, 5).owned_rotated(RotateAngle::Degrees90);
+    draw_f(&mut owned);
+    assert_eq!(
+        to_ascii(&borrowed),
+        to_ascii(&owned.into_inner().into_inner())
+    );
+
+    let mut borrowed = display(6, 5);
+    draw_f(&mut borrowed.cropped(&area));
+    let mut owned = display(6, 5).owned_cropped(&area);
+    draw_f(&mut owned);
+    assert_eq!(
+        to_ascii(&borrowed),
+        to_ascii(&owned.into_inner().into_inner())
+    );
+
+    let mut borrowed = display(6, 5);
+    draw_f(&mut borrowed.affine(matrix));
+    let mut owned = display(6, 5).owned_oriented(Orientation::Transpose);
+    draw_f(&mut owned);
+    assert_eq!(
+        to_ascii(&borrowed),
+        to_ascii(&owned.into_inner().into_inner())
+    );
+}
+
+#[test]
+fn owned_color_converted_and_blended() {
+    let mut converted = gray_display(3, 1).owned_color_converted::<BinaryColor>();
+    Pixel(Point::new(1, 0), BinaryColor::On)
+        .draw(&mut converted)
+        .unwrap();
+    assert_snapshot!(
+        "owned_color_converted",
+        &converted.into_inner().into_inner()
+    );
+
+    let mut blended = gray_display(3, 1).owned_blended();
+    blended
+        .fill_solid(
+            &Rectangle::new(Point::zero(), Size::new(3, 1)),
+            Alpha::opaque(Gray8::new(0x80)),
+        )
+        .unwrap();
+    Pixel(Point::new(0, 0), Alpha::new(Gray8::WHITE, 128))
+        .draw(&mut blended)
+        .unwrap();
+    Pixel(Point::new(2, 0), Alpha::new(Gray8::WHITE, 0))
+        .draw(&mut blended)
+        .unwrap();
+    assert_snapshot!("owned_blended", &blended.into_inner().into_inner());
+}
+
+#[test]
+fn owned_flushing_and_tee_forward_flush() {
+    let mut flushing = display(2, 2).owned_flushing(|display| display.flush());
+    flushing.flush().unwrap();
+    assert_eq!(flushing.into_inner().into_inner().flushes(), 1);
+
+    let mut tee = display(4, 3).owned_tee(display(4, 3));
+    draw_f(&mut tee);
+    tee.flush().unwrap();
+
+    let (first, second) = tee.into_inner().into_inner();
+    assert_eq!(to_ascii(&first), to_ascii(&second));
+    assert_eq!((first.flushes(), second.flushes()), (1, 1));
+}
+
+//
+// Pipeline
+//
+
+#[test]
+fn owned_live_bounds_need_no_mutation() {
+    fn assert_sync<T: Sync>(_: &T) {}
+
+    let area = Rectangle::new(Point::zero(), Size::new(2, 2));
+    let mut live = display(6, 5)
+        .owned_cropped(&area)
+        .with_bounds_mode(BoundsMode::Live);
+    assert_sync(&live);
+
+    live.inner_mut()
+        .set_area(&Rectangle::new(Point::new(3, 2), Size::new(4, 4)));
+    let live = &live;
+    assert_eq!(live.bounding_box().size, Size::new(3, 3));
+    assert_eq!(live.bounding_box().top_left, Point::zero());
+}
+
+fn assert_bounds_hook<T>(mut transformer: T)
+where
+    T: Transformer,
+{
+    let expected = transformer.transform().bounding_box();
+
+    assert_eq!(Transformer::bounding_box(&transformer), Some(expected));
+}
+
+#[test]
+fn transformer_bounds_match_their_draw_targets() {
+    let area = Rectangle::new(Point::new(1, 1), Size::new(9, 3));
+
+    assert_bounds_hook(TranslatedT(display(6, 5), Point::new(1, -2)));
+    assert_bounds_hook(CroppedT(display(6, 5), area));
+    assert_bounds_hook(ClippedT(display(6, 5), area));
+    assert_bounds_hook(ColorConvertedT::<_, BinaryColor>(
+        gray_display(6, 5),
+        PhantomData,
+    ));
+    assert_bounds_hook(RotatedT(display(6, 5), RotateAngle::Degrees90));
+    assert_bounds_hook(OrientedT(display(6, 5), Orientation::Transpose));
+    assert_bounds_hook(ScaledT(display(6, 5), Size::new(3, 2), ScaleMode::Nearest));
+    assert_bounds_hook(AffineT(
+        display(6, 5),
+        AffineMatrix::shearing(AffineMatrix::ONE / 2, 0),
+    ));
+    assert_bounds_hook(FlushingT(
+        display(6, 5),
+        |display: &mut RecordingDisplay<BinaryColor>| display.flush(),
+    ));
+    assert_bounds_hook(TeeT(display(6, 5), display(2, 2)));
+    assert_bounds_hook(BlendedT(gray_display(6, 5)));
+    assert_bounds_hook(
+        display(6, 5)
+            .pipeline()
+            .rotate(RotateAngle::Degrees90)
+            .crop(&area)
+            .build()
+            .into_inner(),
+    );
+}
+
+#[test]
+fn pipeline_matches_nested_adapters() {
+    let area = Rectangle::new(Point::new(1, 0), Size::new(4, 4));
+
+    let mut nested = display(6, 5);
+    draw_f(&mut nested.rotated(RotateAngle::Degrees90).cropped(&area));
+
+    let mut pipeline = display(6, 5)
+        .pipeline()
+        .rotate(RotateAngle::Degrees90)
+        .crop(&area)
+        .flushing_target()
+        .build();
+    draw_f(&mut pipeline);
+    pipeline.flush().unwrap();
+
+    let mut pipeline = pipeline.into_inner().into_inner();
+    assert_eq!(to_ascii(&nested), to_ascii(&pipeline));
+    assert_eq!(pipeline.take_ops().last(), Some(&Op::Flush));
+}
+
+#[test]
+fn pipeline_clips_like_nested_adapters() {
+    // Unlike `draw_f` this doesn't depend on the bounding box, which clipping changes
+    fn draw_triangle<D>(target: &mut D)
+    where
+        D: DrawTarget<Color = BinaryColor>,
+        D::Error: Debug,
+    {
+        let area = Rectangle::new(Point::zero(), Size::new(6, 4));
+
+        target
+            .draw_iter(
+                area.points()
+                    .filter(|point| point.x <= point.y + 1)
+                    .map(|point| Pixel(point, BinaryColor::On)),
+            )
+            .unwrap();
+    }
+
+    let area = Rectangle::new(Point::new(1, 1), Size::new(3, 2));
+
+    let mut nested = display(6, 4);
+    draw_triangle(
+        &mut nested
+            .translated(Point::new(1, 0))
+            .oriented(Orientation::FlipVertical)
+            .clipped(&area),
+    );
+
+    let mut pipeline = display(6, 4)
+        .pipeline()
+        .translate(Point::new(1, 0))
+        .orient(Orientation::FlipVertical)
+        .clip(&area)
+        .build();
+    draw_triangle(&mut pipeline);
+    assert_eq!(to_ascii(&nested), to_ascii(pipeline.inner().inner()));
+
+    // Clearing only touches the clipped area
+    let mut pipeline = display(6, 4).pipeline().clip(&area).build();
+    pipeline.clear(BinaryColor::On).unwrap();
+    assert_eq!(
+        to_ascii(pipeline.inner().inner()),
+        "......\n.###..\n.###..\n......\n"
+    );
+}
+
+#[test]
+fn pipeline_forwards_contiguous_fills_through_offsets_and_clips() {
+    let mut pipeline = display(6, 4)
+        .pipeline()
+        .translate(Point::new(1, 0))
+        .clip(&Rectangle::new(Point::new(1, 1), Size::new(3, 2)))
+        .build();
+
+    // A checkerboard, of which only the clipped 3x2 remains
+    let area = Rectangle::new(Point::zero(), Size::new(4, 4));
+    let colors = area.points().map(|point| (point.x + point.y) % 2 == 0);
+    pipeline
+        .fill_contiguous(&area, colors.map(BinaryColor::from))
+        .unwrap();
+
+    let mut parent = pipeline.into_inner().into_inner();
+    assert_eq!(
+        parent.take_ops(),
+        [Op::FillContiguous(Rectangle::new(
+            Point::new(2, 1),
+            Size::new(3, 2)
+        ))]
+    );
+    assert_eq!(to_ascii(&parent), "......\n..#.#.\n...#..\n......\n");
+}
+
+#[test]
+fn pipeline_keeps_integer_stages_exact() {
+    // Neither 7 / 3 nor 5 / 4 is exact in fixed point
+    let shear = AffineMatrix::shearing(AffineMatrix::ONE / 3, 0);
+
+    let mut nested = display(7, 5);
+    draw_f(&mut nested.translated(Point::new(1, 0)).scaled(Size::new(3, 4)));
+    let mut pipeline = display(7, 5)
+        .pipeline()
+        .translate(Point::new(1, 0))
+        .scale(Size::new(3, 4))
+        .build();
+    draw_f(&mut pipeline);
+    assert_eq!(to_ascii(&nested), to_ascii(pipeline.inner().inner()));
+
+    let mut nested = display(7, 5);
+    draw_f(
+        &mut nested
+            .scaled(Size::new(3, 4))
+            .translated(Point::new(1, 1))
+            .affine(shear),
+    );
+    let mut pipeline = display(7, 5)
+        .pipeline()
+        .scale(Size::new(3, 4))
+        .translate(Point::new(1, 1))
+        .affine(shear)
+        .build();
+    draw_f(&mut pipeline);
+    assert_eq!(to_ascii(&nested), to_ascii(pipeline.inner().inner()));
+
+    // Offsets past the 16.16 range of an affine matrix
+    let mut far = display(4, 3)
+        .pipeline()
+        .translate(Point::new(40_000, -40_000))
+        .translate(Point::new(-40_001, 40_000))
+        .build();
+    assert_eq!(
+        far.bounding_box(),
+        Rectangle::new(Point::new(1, 0), Size::new(4, 3))
+    );
+    far.draw_iter([Pixel(Point::new(1, 0), BinaryColor::On)])
+        .unwrap();
+    assert_eq!(
+        far.inner().inner().get_pixel(Point::zero()),
+        Some(BinaryColor::On)
+    );
+}
+
+//
+// Tiled
+//
+
+#[test]
+fn tiled_spans_every_tile() {
+    let tiled = Tiled::new([display(3, 2), display(3, 2), display(3, 2)], 2).unwrap();
+    assert_eq!(tiled.rows(), 2);
+    assert_eq!(
+        tiled.bounding_box(),
+        Rectangle::new(Point::zero(), Size::new(6, 4))
+    );
+}
+
+#[test]
+fn tiled_snapshot() {
+    let mut tiled = Tiled::new([display(3, 2), display(3, 2), display(3, 2)], 2).unwrap();
+    draw_f(&mut tiled);
+
+    for (index, tile) in tiled.tiles().iter().enumerate() {
+        assert_snapshot!(format!("tiled_{}", index), tile);
+    }
+}
+
+#[test]
+fn tiled_discards_positions_without_a_tile() {
+    let mut tiled = Tiled::new([display(3, 2), display(3, 2), display(3, 2)], 2).unwrap();
+
+    assert_eq!(tiled.tile_at(Point::new(4, 1)), Some(1));
+    assert_eq!(tiled.tile_at(Point::new(1, 3)), Some(2));
+    assert_eq!(tiled.tile_at(Point::new(4, 3)), None);
+
+    tiled
+        .draw_iter([
+            Pixel(Point::new(4, 3), BinaryColor::On),
+            Pixel(Point::new(1, 3), BinaryColor::On),
+        ])
+        .unwrap();
+    tiled.flush().unwrap();
+
+    let [mut first, mut second, mut third] = tiled.into_inner();
+    assert_eq!(third.get_pixel(Point::new(1, 1)), Some(BinaryColor::On));
+    assert_eq!(third.take_ops(), [Op::DrawIter { pixels: 1 }, Op::Flush]);
+    assert_eq!(first.take_ops(), [Op::Flush]);
+    assert_eq!(second.take_ops(), [Op::Flush]);
+}
+
+#[test]
+fn tiled_rejects_tiles_of_different_sizes() {
+    let err = Tiled::new([display(3, 2), display(3, 2), display(2, 3)], 2)
+        .err()
+        .unwrap();
+
+    assert_eq!(
+        err,
+        TileSizeError {
+            index: 2,
+            expected: Size::new(3, 2),
+            actual: Size::new(2, 3),
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "Tile 2 is 2x3, expected 3x2 like the first tile"
+    );
+}
+
+//
+// Tee
+//
+
+/// A 4x4 target counting the pixels it receives, every operation fails with `error` if set.
+#[derive(Default)]
+struct Failing {
+    error: Option<u8>,
+    pixels: usize,
+}
+
+impl Failing {
+    fn new(error: u8) -> Self {
+        Self {
+            error: Some(error),
+            pixels: 0,
+        }
+    }
+
+    fn result(&self) -> Result<(), u8> {
+        self.error.map_or(Ok(()), Err)
+    }
+}
+
+impl DrawTarget for Failing {
+    type Color = BinaryColor;
+    type Error = u8;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.pixels += pixels.into_iter().count();
+        self.result()
+    }
+}
+
+impl OriginDimensions for Failing {
+    fn size(&self) -> Size {
+        Size::new(4, 4)
+    }
+}
+
+#[test]
+fn tee_combines_errors() {
+    let (mut first, mut second) = (Failing::new(1), Failing::new(2));
+    let result = Pixel(Point::zero(), BinaryColor::On).draw(&mut first.tee(&mut second));
+    assert!(matches!(result, Err(TeeError::Both(1, 2))));
+
+    let (mut first, mut second) = (display(4, 4), Failing::new(3));
+    let result = draw_f_result(&mut first.tee(&mut second));
+    assert!(matches!(result, Err(TeeError::Second(3))));
+    assert_eq!(second.pixels, 4);
+    assert_eq!(
+        first.take_ops(),
+        [Op::FillSolid(
+            Rectangle::new(Point::zero(), Size::new(4, 1)),
+            BinaryColor::On
+        )]
+    );
+}
+
+#[test]
+fn tee_forwards_contiguous_fills() {
+    let (mut first, mut second) = (display(4, 4), display(4, 4));
+    let area = Rectangle::new(Point::zero(), Size::new(4, 4));
+
+    first
+        .tee(&mut second)
+        .fill_contiguous(&area, core::iter::repeat(BinaryColor::On).take(5))
+        .unwrap();
+
+    let expected = [
+        Op::FillContiguous(Rectangle::new(Point::zero(), Size::new(4, 1))),
+        Op::FillContiguous(Rectangle::new(Point::new(0, 1), Size::new(1, 1))),
+    ];
+    assert_eq!(first.take_ops(), expected);
+    assert_eq!(second.take_ops(), expected);
+}
+
+/// Like [`draw_f`] without the middle bar, stopping at the first error.
+fn draw_f_result<D>(target: &mut D) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    let bbox = target.bounding_box();
+
+    target.fill_solid(
+        &Rectangle::new(bbox.top_left, Size::new(bbox.size.width, 1)),
+        BinaryColor::On,
+    )?;
+    target.fill_solid(
+        &Rectangle::new(bbox.top_left, Size::new(1, bbox.size.height)),
+        BinaryColor::On,
+    )
+}
+
+#[test]
+fn fan_out_draws_to_every_target() {
+    let mut displays = [display(4, 3), display(4, 3), display(4, 3)];
+    let mut fan_out = FanOut::new(&mut displays);
+
+    draw_f(&mut fan_out);
+    fan_out.flush().unwrap();
+    assert_eq!(fan_out.bounding_box().size, Size::new(4, 3));
+
+    let mut expected = display(4, 3);
+    draw_f(&mut expected);
+    for display in &displays {
+        assert_eq!(to_ascii(display), to_ascii(&expected));
+        assert_eq!(display.flushes(), 1);
+    }
+
+    assert_eq!(
+        FanOut::<RecordingDisplay<BinaryColor>>::new(&mut []).bounding_box(),
+        Rectangle::zero()
+    );
+}
+
+#[test]
+fn fan_out_aggregates_errors() {
+    let mut targets = [
+        Failing::default(),
+        Failing::new(1),
+        Failing::default(),
+        Failing::new(2),
+    ];
+
+    let result = FanOut::new(&mut targets)
+        .draw_iter((0..3).map(|x| Pixel(Point::new(x, 0), BinaryColor::On)));
+
+    assert_eq!(
+        result,
+        Err(FanOutError {
+            index: 1,
+            error: 1,
+            failed: 2
+        })
+    );
+    assert!(targets.iter().all(|target| target.pixels == 3));
+}
+
+//
+// Layers
+//
+
+fn layer(width: usize, height: usize, luma: u8) -> PackedFramebuffer<Gray8, Vec<u8>> {
+    PackedFramebuffer::new(vec![luma; width * height], width, height).unwrap()
+}
+
+#[test]
+fn layers_snapshot() {
+    let layer = |width, height| {
+        PackedFramebuffer::<Gray8, _>::new(vec![0; width * height], width, height).unwrap()
+    };
+
+    let mut layers = Layers::new(
+        gray_display(6, 4),
+        vec![
+            Layer::new(layer(6, 4)),
+            Layer::new(layer(3, 3))
+                .with_offset(Point::new(2, 1))
+                .with_transparency(Transparency::Key(Gray8::BLACK)),
+        ],
+    );
+
+    draw_gradient(&mut layers.layer_mut(0));
+    Pixel(Point::new(1, 1), Gray8::WHITE)
+        .draw(&mut layers.layer_mut(1))
+        .unwrap();
+    layers.flush().unwrap();
+    assert_snapshot!("layers", layers.target());
+
+    layers.set_visible(1, false);
+    layers.compose().unwrap();
+    assert_snapshot!("layers_hidden", layers.target());
+}
+
+#[test]
+fn layers_draw_higher_z_on_top() {
+    let mut layers = Layers::new(
+        gray_display(3, 1),
+        [
+            Layer::new(layer(2, 1, 0xff)).with_z(1),
+            Layer::new(layer(2, 1, 0x80)).with_offset(Point::new(1, 0)),
+        ],
+    );
+
+    layers.compose().unwrap();
+    assert_eq!(to_ascii(layers.target()), "FF8\n");
+
+    layers.set_z(1, 2);
+    layers.compose().unwrap();
+    assert_eq!(to_ascii(layers.target()), "F88\n");
+}
+
+#[test]
+fn layers_compose_only_changed_areas() {
+    let mut layers = Layers::new(
+        gray_display(4, 3),
+        [Layer::new(layer(2, 2, 0xff)).with_offset(Point::new(1, 1))],
+    );
+
+    layers.compose().unwrap();
+    assert_eq!(to_ascii(layers.target()), "0000\n0FF0\n0FF0\n");
+    layers.target_mut().take_ops();
+
+    // Nothing changed, nothing is drawn
+    layers.compose().unwrap();
+    assert_eq!(layers.target_mut().take_ops(), []);
+
+    // Pixels without an opaque layer are left untouched
+    layers.set_visible(0, false);
+    layers.compose().unwrap();
+    assert_eq!(to_ascii(layers.target()), "0000\n0FF0\n0FF0\n");
+
+    Pixel(Point::new(1, 1), Gray8::new(0x40))
+        .draw(layers.target_mut())
+        .unwrap();
+    layers.set_visible(0, true);
+    layers.compose().unwrap();
+    assert_eq!(to_ascii(layers.target()), "0000\n0FF0\n0FF0\n");
+}
+
+#[test]
+fn layers_fill_uncovered_pixels_with_the_background() {
+    let background = Gray8::new(0x40);
+    let mut layers = Layers::new(
+        gray_display(4, 3),
+        [
+            Layer::new(PackedFramebuffer::<Gray8, _>::new(vec![0xff; 4], 2, 2).unwrap())
+                .with_offset(Point::new(1, 1)),
+        ],
+    )
+    .with_background(background);
+
+    layers.compose().unwrap();
+    let shown: Vec<_> = pixels(layers.target());
+    assert_eq!(
+        shown
+            .iter()
+            .filter(|color| **color == Some(Gray8::WHITE))
+            .count(),
+        4
+    );
+    assert_eq!(
+        shown
+            .iter()
+            .filter(|color| **color == Some(background))
+            .count(),
+        8
+    );
+
+    // Hiding the layer leaves no stale pixels behind
+    layers.set_visible(0, false);
+    layers.compose().unwrap();
+    assert!(pixels(layers.target())
+        .iter()
+        .all(|color| *color == Some(background)));
+
+    layers.set_background(Some(Gray8::BLACK));
+    layers.compose().unwrap();
+    assert!(pixels(layers.target())
+        .iter()
+        .all(|color| *color == Some(Gray8::BLACK)));
+}
+
+#[test]
+fn layers_mask_out_pixels() {
+    let layer = || PackedFramebuffer::<Gray8, _>::new(vec![0xff; 6], 3, 2).unwrap();
+    let on = [Point::new(0, 0), Point::new(2, 1)];
+
+    // A packed mask, and any other readable binary target
+    let mut packed = PackedFramebuffer::<BinaryColor, _>::new(vec![0; 2], 3, 2).unwrap();
+    let mut recorded = display(3, 2);
+    for point in on {
+        Pixel(point, BinaryColor::On).draw(&mut packed).unwrap();
+        Pixel(point, BinaryColor::On).draw(&mut recorded).unwrap();
+    }
+
+    let mut packed = Layers::new(
+        gray_display(3, 2),
+        [Layer::new(layer()).with_transparency(Transparency::Mask(packed))],
+    );
+    packed.compose().unwrap();
+
+    let mut recorded = Layers::new(gray_display(3, 2), [Layer::masked(layer(), recorded)]);
+    recorded.compose().unwrap();
+
+    for point in Rectangle::new(Point::zero(), Size::new(3, 2)).points() {
+        let expected = if on.contains(&point) {
+            Gray8::WHITE
+        } else {
+            Gray8::BLACK
+        };
+
+        assert_eq!(
+            packed.target().get_pixel(point),
+            Some(expected),
+            "{:?}",
+            point
+        );
+        assert_eq!(
+            recorded.target().get_pixel(point),
+            Some(expected),
+            "{:?}",
+            point
+        );
+    }
+}
+
+//
+// DynFlushable
+//
+
+fn pixels<T>(target: &T) -> Vec<Option<T::Color>>
+where
+    T: ReadableTarget,
+{
+    target.rows().flatten().collect()
+}
+
+#[test]
+fn boxed_flushable_forwards_to_the_inner_target() {
+    let flush = |display: &mut RecordingDisplay<Gray8>| display.flush();
+    let area = Rectangle::new(Point::new(1, 2), Size::new(3, 4));
+
+    let mut display = gray_display(70, 20);
+    let mut boxed = BoxedFlushable::new(display.flushing(flush));
+    assert_eq!(
+        boxed.bounding_box(),
+        Rectangle::new(Point::zero(), Size::new(70, 20))
+    );
+
+    boxed.clear(Gray8::new(0x10)).unwrap();
+    boxed.fill_solid(&area, Gray8::new(0x20)).unwrap();
+    // Pixels are forwarded in chunks
+    boxed
+        .draw_iter((0..130).map(|i| Pixel(Point::new(i % 70, i / 70), Gray8::WHITE)))
+        .unwrap();
+    boxed.flush().unwrap();
+    drop(boxed);
+
+    assert_eq!(
+        display.take_ops(),
+        [
+            Op::Clear(Gray8::new(0x10)),
+            Op::FillSolid(area, Gray8::new(0x20)),
+            Op::DrawIter { pixels: 64 },
+            Op::DrawIter { pixels: 64 },
+            Op::DrawIter { pixels: 2 },
+            Op::Flush
+        ]
+    );
+    assert_eq!(display.get_pixel(Point::new(59, 1)), Some(Gray8::WHITE));
+    assert_eq!(display.get_pixel(Point::new(60, 1)), Some(Gray8::new(0x10)));
+}
+
+#[test]
+fn boxed_flushable_forwards_wide_areas_in_chunks() {
+    let flush = |display: &mut RecordingDisplay<Gray8>| display.flush();
+
+    let mut display = gray_display(70, 20);
+    let mut boxed = BoxedFlushable::new(display.flushing(flush));
+    draw_gradient(&mut boxed);
+    boxed
+        .draw_iter((0..130).map(|i| Pixel(Point::new(i % 70, i / 70), Gray8::WHITE)))
+        .unwrap();
+    boxed.flush().unwrap();
+    drop(boxed);
+
+    let mut expected = gray_display(70, 20);
+    draw_gradient(&mut expected);
+    expected
+        .draw_iter((0..130).map(|i| Pixel(Point::new(i % 70, i / 70), Gray8::WHITE)))
+        .unwrap();
+    assert_eq!(pixels(&display), pixels(&expected));
+
+    // Rows wider than a chunk are split, pixels are forwarded in chunks
+    let ops = display.take_ops();
+    assert_eq!(ops.len(), 2 * 20 + 3 + 1);
+    assert_eq!(
+        ops[..2],
+        [
+            Op::FillContiguous(Rectangle::new(Point::zero(), Size::new(64, 1))),
+            Op::FillContiguous(Rectangle::new(Point::new(64, 0), Size::new(6, 1))),
+        ]
+    );
+    assert_eq!(
+        ops[40..],
+        [
+            Op::DrawIter { pixels: 64 },
+            Op::DrawIter { pixels: 64 },
+            Op::DrawIter { pixels: 2 },
+            Op::Flush
+        ]
+    );
+}
+
+#[test]
+fn boxed_flushable_fills_narrow_areas_by_whole_rows() {
+    let mut display = gray_display(5, 30);
+    let mut boxed = BoxedFlushable::new(display.noop_flushing());
+
+    // Three colours short of twelve rows
+    let area = Rectangle::new(Point::zero(), Size::new(5, 30));
+    boxed
+        .fill_contiguous(&area, core::iter::repeat(Gray8::WHITE).take(57))
+        .unwrap();
+    drop(boxed);
+
+    assert_eq!(
+        display.take_ops(),
+        [
+            Op::FillContiguous(Rectangle::new(Point::zero(), Size::new(5, 11))),
+            Op::FillContiguous(Rectangle::new(Point::new(0, 11), Size::new(2, 1))),
+        ]
+    );
+    assert_eq!(
+        pixels(&display)
+            .iter()
+            .filter(|color| **color == Some(Gray8::WHITE))
+            .count(),
+        57
+    );
+}
+
+#[test]
+fn boxed_flushables_of_different_types_share_a_vec() {
+    let flush = |display: &mut RecordingDisplay<Gray8>| display.flush();
+    let (mut first, mut second) = (gray_display(2, 2), gray_display(3, 1));
+
+    let mut displays = vec![
+        BoxedFlushable::new(first.noop_flushing()),
+        BoxedFlushable::new(second.flushing(flush)),
+    ];
+
+    for display in displays.iter_mut() {
+        Pixel(Point::zero(), Gray8::WHITE).draw(display).unwrap();
+        display.flush().unwrap();
+    }
+    drop(displays);
+
+    assert_eq!(first.take_ops(), [Op::DrawIter { pixels: 1 }]);
+    assert_eq!(second.take_ops(), [Op::DrawIter { pixels: 1 }, Op::Flush]);
+}
+
+//
+// Buffered
+//
+
+#[test]
+fn buffered_flushes_only_changes() {
+    let mut buffered = Buffered::new_alloc(gray_display(8, 4));
+
+    buffered.flush().unwrap();
+    assert_eq!(buffered.target.take_ops(), [Op::Flush]);
+
+    Pixel(Point::new(2, 1), Gray8::WHITE)
+        .draw(&mut buffered)
+        .unwrap();
+    Pixel(Point::new(3, 2), Gray8::WHITE)
+        .draw(&mut buffered)
+        .unwrap();
+    buffered.flush().unwrap();
+
+    assert_eq!(
+        buffered.target.take_ops(),
+        [
+            Op::FillContiguous(Rectangle::new(Point::new(2, 1), Size::new(2, 2))),
+            Op::Flush
+        ]
+    );
+    assert_snapshot!("buffered", &buffered.target);
+
+    // Redrawing the same pixel changes nothing
+    Pixel(Point::new(2, 1), Gray8::WHITE)
+        .draw(&mut buffered)
+        .unwrap();
+    buffered.flush().unwrap();
+    assert_eq!(buffered.target.take_ops(), [Op::Flush]);
+}
+
+#[test]
+fn buffered_reads_back_unflushed_changes() {
+    let mut buffered = Buffered::new_alloc(gray_display(4, 2));
+
+    Pixel(Point::new(1, 1), Gray8::WHITE)
+        .draw(&mut buffered)
+        .unwrap();
+
+    assert_eq!(buffered.get_pixel(Point::new(1, 1)), Some(Gray8::WHITE));
+    assert_eq!(
+        buffered.target.get_pixel(Point::new(1, 1)),
+        Some(Gray8::BLACK)
+    );
+    assert_eq!(buffered.get_pixel(Point::new(4, 1)), None);
+}
+
+#[test]
+fn buffered_flushes_distant_changes_separately() {
+    let mut buffered = Buffered::new_alloc(gray_display(8, 4));
+
+    Pixel(Point::new(0, 0), Gray8::WHITE)
+        .draw(&mut buffered)
+        .unwrap();
+    Pixel(Point::new(7, 3), Gray8::WHITE)
+        .draw(&mut buffered)
+        .unwrap();
+    buffered.flush().unwrap();
+
+    let mut ops = buffered.target.take_ops();
+    ops[..2].sort_by_key(|op| match op {
+        Op::FillContiguous(area) => area.top_left.x,
+        _ => i32::MAX,
+    });
+    assert_eq!(
+        ops,
+        [
+            Op::FillContiguous(Rectangle::new(Point::new(0, 0), Size::new(1, 1))),
+            Op::FillContiguous(Rectangle::new(Point::new(7, 3), Size::new(1, 1))),
+            Op::Flush
+        ]
+    );
+}
+
+fn rect(x: i32, y: i32, width: u32, height: u32) -> Rectangle {
+    Rectangle::new(Point::new(x, y), Size::new(width, height))
+}
+
+#[test]
+fn dirty_regions_merge_touching_areas() {
+    let mut dirty = DirtyRegions::new();
+
+    dirty.add(rect(0, 0, 2, 2));
+    dirty.add(rect(2, 0, 2, 2));
+    assert_eq!(dirty.regions(), [rect(0, 0, 4, 2)]);
+
+    // Bridging two separate regions merges all three
+    dirty.add(rect(10, 0, 2, 2));
+    dirty.add(rect(4, 1, 6, 1));
+    assert_eq!(dirty.regions(), [rect(0, 0, 12, 2)]);
+
+    dirty.add(rect(0, 5, 0, 3));
+    assert_eq!(dirty.regions(), [rect(0, 0, 12, 2)]);
+
+    dirty.clear();
+    assert!(dirty.regions().is_empty());
+}
+
+#[test]
+fn dirty_regions_merge_the_cheapest_pair_when_full() {
+    let mut dirty = DirtyRegions::new();
+
+    for index in 0..DIRTY_REGIONS as i32 {
+        dirty.add(rect(index * 10, 0, 1, 1));
+    }
+    assert_eq!(dirty.regions().len(), DIRTY_REGIONS);
+
+    dirty.add(rect(72, 0, 1, 1));
+    assert_eq!(dirty.regions().len(), DIRTY_REGIONS);
+    assert!(dirty.regions().contains(&rect(70, 0, 3, 1)));
+}
+
+#[test]
+fn buffered_storage_does_not_change_the_output() {
+    fn draw<T>(buffered: &mut T)
+    where
+        T: Flushable<Color = Gray8>,
+        T::Error: Debug,
+    {
+        buffered.clear(Gray8::new(0x40)).unwrap();
+        Pixel(Point::new(5, 3), Gray8::WHITE)
+            .draw(buffered)
+            .unwrap();
+        buffered.flush().unwrap();
+    }
+
+    let (mut draw_buf, mut reference_buf) = ([0; 32], [0; 32]);
+    let mut borrowed = gray_display(8, 4).owned_buffered(&mut draw_buf, &mut reference_buf);
+    let mut owned = gray_display(8, 4).owned_alloc_buffered();
+    draw(&mut borrowed);
+    draw(&mut owned);
+
+    assert_eq!(borrowed.target.take_ops(), owned.target.take_ops());
+    assert_eq!(to_ascii(&borrowed.target), to_ascii(&owned.target));
+    assert_eq!(owned.target.get_pixel(Point::new(5, 3)), Some(Gray8::WHITE));
+}
+
+#[test]
+fn buffered_rejects_small_buffers() {
+    let result = Buffered::try_new(vec![0; 31], vec![0; 32], gray_display(8, 4));
+    let err = BufferSizeError {
+        required: 32,
+        actual: 31,
+    };
+
+    assert_eq!(result.err(), Some(BufferedError::BufferSize(err)));
+    assert_eq!(
+        err.to_string(),
+        "Framebuffer too small: 32 bytes required, 31 bytes provided"
+    );
+
+    let (mut draw_buf, mut reference_buf) = ([0; 32], [0; 16]);
+    let err = gray_display(8, 4)
+        .try_owned_buffered(&mut draw_buf, &mut reference_buf)
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.to_string(),
+        "Framebuffer too small: 32 bytes required, 16 bytes provided"
+    );
+    assert!(std::error::Error::source(&err).is_some());
+}
+
+#[test]
+#[should_panic(expected = "Framebuffer too small")]
+fn buffered_new_panics_on_small_buffers() {
+    Buffered::new(vec![0; 32], vec![0; 16], gray_display(8, 4));
+}
+
+#[test]
+#[should_panic(expected = "Framebuffer too small")]
+fn owned_buffered_panics_on_small_buffers() {
+    let (mut draw_buf, mut reference_buf) = ([0; 16], [0; 32]);
+    gray_display(8, 4).owned_buffered(&mut draw_buf, &mut reference_buf);
+}
+
+//
+// PackedFramebuffer
+//
+
+fn read<C, S>(framebuffer: &PackedFramebuffer<C, S>, point: Point) -> C
+where
+    C: PackedColor,
+    S: AsRef<[u8]> + AsMut<[u8]>,
+{
+    let (byte_offset, bits_offset) = framebuffer.pixel_offset(point);
+
+    framebuffer.get(byte_offset, bits_offset)
+}
+
+fn round_trip<C>(colors: &[C])
+where
+    C: PackedColor + Debug,
+{
+    for (layout, bit_order, row_alignment) in [
+        (MemoryLayout::Horizontal, BitOrder::LsbFirst, 1),
+        (MemoryLayout::Horizontal, BitOrder::MsbFirst, 4),
+        (MemoryLayout::Vertical, BitOrder::LsbFirst, 1),
+        (MemoryLayout::Vertical, BitOrder::MsbFirst, 2),
+    ] {
+        for (width, height) in [(1, 1), (3, 5), (7, 9), (9, 3), (13, 8)] {
+            let size = Size::new(width as u32, height as u32);
+            let len =
+                PackedFramebuffer::<C, Vec<u8>>::layout_buffer_size(size, layout, row_alignment);
+
+            let mut framebuffer =
+                PackedFramebuffer::with_layout(vec![0; len], width, height, layout, bit_order)
+                    .and_then(|framebuffer| framebuffer.with_row_alignment(row_alignment))
+                    .unwrap();
+
+            let color_at = |point: Point| colors[(point.x * 3 + point.y) as usize % colors.len()];
+            let bbox = framebuffer.bounding_box();
+
+            framebuffer
+                .fill_contiguous(&bbox, bbox.points().map(color_at))
+                .unwrap();
+
+            for point in bbox.points() {
+                assert_eq!(
+                    read(&framebuffer, point),
+                    color_at(point),
+                    "{:?} {:?} {}x{} {:?}",
+                    layout,
+                    bit_order,
+                    width,
+                    height,
+                    point
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn packed_framebuffer_round_trips_every_depth() {
+    round_trip(&[BinaryColor::On, BinaryColor::Off, BinaryColor::Off]);
+    round_trip(&[Gray2::new(1), Gray2::new(3), Gray2::new(0), Gray2::new(2)]);
+    round_trip(&[Gray4::new(9), Gray4::new(0), Gray4::new(15)]);
+    round_trip(&[Gray8::new(7), Gray8::new(200), Gray8::new(0)]);
+    round_trip(&[Rgb565::RED, Rgb565::new(3, 40, 17), Rgb565::BLACK]);
+    round_trip(&[Rgb888::GREEN, Rgb888::new(1, 2, 3)]);
+}
+
+/// Checks the stride, the buffer length and where every pixel lands against a straightforward
+/// model of the layout, by setting each pixel to all ones in an otherwise empty buffer.
+fn check_layout<C>()
+where
+    C: PackedColor + Debug,
+{
+    type Framebuffer<C> = PackedFramebuffer<C, Vec<u8>>;
+
+    let bits = Framebuffer::<C>::BITS_PER_PIXEL;
+    let ones = C::from_bits(((1_u64 << C::Raw::BITS_PER_PIXEL) - 1) as u32);
+    let pixels_per_byte = max(8 / bits, 1);
+    let bytes_per_pixel = (bits + 7) / 8;
+
+    for layout in [MemoryLayout::Horizontal, MemoryLayout::Vertical] {
+        for bit_order in [BitOrder::LsbFirst, BitOrder::MsbFirst] {
+            for row_alignment in [1, 2, 3, 4, 8] {
+                for (width, height) in
+                    (1..=9_usize).flat_map(|w| (1..=9_usize).map(move |h| (w, h)))
+                {
+                    let context = format!(
+                        "{}bpp {:?} {:?} {}-aligned {}x{}",
+                        bits, layout, bit_order, row_alignment, width, height
+                    );
+
+                    let (row_bytes, rows) = match layout {
+                        MemoryLayout::Horizontal => ((width * bits + 7) / 8, height),
+                        MemoryLayout::Vertical => (
+                            width * bytes_per_pixel,
+                            (height + pixels_per_byte - 1) / pixels_per_byte,
+                        ),
+                    };
+                    let stride = (row_bytes + row_alignment - 1) / row_alignment * row_alignment;
+
+                    let size = Size::new(width as u32, height as u32);
+                    let len = Framebuffer::<C>::layout_buffer_size(size, layout, row_alignment);
+                    assert_eq!(len, stride * rows, "{}", context);
+                    assert!(
+                        Framebuffer::<C>::with_layout(
+                            vec![0; len - 1],
+                            width,
+                            height,
+                            layout,
+                            bit_order
+                        )
+                        .and_then(|framebuffer| framebuffer.with_row_alignment(row_alignment))
+                        .is_err(),
+                        "{}",
+                        context
+                    );
+
+                    // Spare bytes past the end must not show up in `as_bytes`
+                    let mut framebuffer = PackedFramebuffer::with_layout(
+                        vec![0; len + 3],
+                        width,
+                        height,
+                        layout,
+                        bit_order,
+                    )
+                    .and_then(|framebuffer| framebuffer.with_row_alignment(row_alignment))
+                    .unwrap();
+                    assert_eq!(framebuffer.stride(), stride, "{}", context);
+                    assert_eq!(framebuffer.as_bytes().len(), len, "{}", context);
+
+                    for point in framebuffer.bounding_box().points() {
+                        let (x, y) = (point.x as usize, point.y as usize);
+                        let (byte_offset, _) =
+                            Framebuffer::<C>::locate(layout, bit_order, stride, x, y);
+                        let expected = match layout {
+                            MemoryLayout::Horizontal => y * stride + x * bits / 8,
+                            MemoryLayout::Vertical => {
+                                y / pixels_per_byte * stride + x * bytes_per_pixel
+                            }
+                        };
+                        assert_eq!(byte_offset, expected, "{} {:?}", context, point);
+
+                        framebuffer.draw_iter([Pixel(point, ones)]).unwrap();
+                        let set: u32 = framebuffer
+                            .as_bytes()
+                            .iter()
+                            .map(|byte| byte.count_ones())
+                            .sum();
+                        assert_eq!(
+                            set,
+                            C::Raw::BITS_PER_PIXEL as u32,
+                            "{} {:?}",
+                            context,
+                            point
+                        );
+                        assert!(
+                            framebuffer
+                                .as_bytes()
+                                .iter()
+                                .enumerate()
+                                .all(|(index, byte)| {
+                                    *byte == 0
+                                        || (byte_offset..byte_offset + bytes_per_pixel)
+                                            .contains(&index)
+                                }),
+                            "{} {:?}",
+                            context,
+                            point
+                        );
+                        framebuffer
+                            .draw_iter([Pixel(point, C::from_bits(0))])
+                            .unwrap();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn packed_framebuffer_layouts_match_their_model() {
+    check_layout::<BinaryColor>();
+    check_layout::<Gray2>();
+    check_layout::<Gray4>();
+    check_layout::<Gray8>();
+    check_layout::<Rgb565>();
+    check_layout::<Rgb888>();
+}
+
+#[test]
+fn packed_framebuffer_discards_out_of_bounds() {
+    let mut framebuffer = PackedFramebuffer::<Gray8, _>::new(vec![0; 6], 3, 2).unwrap();
+
+    Rectangle::new(Point::new(-1, 1), Size::new(5, 3))
+        .into_styled(PrimitiveStyle::with_fill(Gray8::WHITE))
+        .draw(&mut framebuffer)
+        .unwrap();
+
+    assert_snapshot!("packed_framebuffer_clipped", &framebuffer);
+}
+
+#[test]
+fn packed_framebuffer_honours_the_byte_order() {
+    let mut big = PackedFramebuffer::<Rgb565, _>::new(vec![0; 4], 2, 1).unwrap();
+    let mut little = PackedFramebuffer::<Rgb565, _>::new(vec![0; 4], 2, 1)
+        .unwrap()
+        .with_byte_order(ByteOrder::LittleEndian);
+    assert_eq!(big.byte_order(), ByteOrder::BigEndian);
+
+    for framebuffer in [&mut big, &mut little] {
+        Pixel(Point::new(1, 0), Rgb565::RED)
+            .draw(framebuffer)
+            .unwrap();
+    }
+
+    assert_eq!(big.buf, [0x00, 0x00, 0xf8, 0x00]);
+    assert_eq!(little.buf, [0x00, 0x00, 0x00, 0xf8]);
+}
+
+#[test]
+fn packed_framebuffer_pages_vertical_layouts() {
+    let size = Size::new(4, 12);
+    let len = PackedFramebuffer::<BinaryColor, Vec<u8>>::layout_buffer_size(
+        size,
+        MemoryLayout::Vertical,
+        1,
+    );
+    assert_eq!(len, 8);
+
+    for (bit_order, expected) in [(BitOrder::LsbFirst, 0x02), (BitOrder::MsbFirst, 0x40)] {
+        let mut framebuffer = PackedFramebuffer::<BinaryColor, _>::with_layout(
+            vec![0; len + 2],
+            4,
+            12,
+            MemoryLayout::Vertical,
+            bit_order,
+        )
+        .unwrap();
+        assert_eq!(framebuffer.layout(), MemoryLayout::Vertical);
+        assert_eq!(framebuffer.bit_order(), bit_order);
+
+        // The second page starts at row 8, one byte per column
+        Pixel(Point::new(2, 9), BinaryColor::On)
+            .draw(&mut framebuffer)
+            .unwrap();
+
+        let mut bytes = [0; 8];
+        bytes[6] = expected;
+        assert_eq!(framebuffer.as_bytes(), bytes);
+    }
+}
+
+//
+// Blended
+//
+
+#[test]
+fn blended_mixes_every_channel() {
+    assert_eq!(Gray8::WHITE.blend(Gray8::BLACK, 0), Gray8::BLACK);
+    assert_eq!(Gray8::WHITE.blend(Gray8::BLACK, 255), Gray8::WHITE);
+    assert_eq!(Gray8::WHITE.blend(Gray8::BLACK, 128), Gray8::new(128));
+    assert_eq!(Gray4::WHITE.blend(Gray4::BLACK, 128), Gray4::new(8));
+    assert_eq!(
+        Rgb888::new(200, 0, 100).blend(Rgb888::new(0, 100, 100), 64),
+        Rgb888::new(50, 75, 100)
+    );
+    assert_eq!(
+        BinaryColor::On.blend(BinaryColor::Off, 127),
+        BinaryColor::Off
+    );
+    assert_eq!(
+        BinaryColor::On.blend(BinaryColor::Off, 128),
+        BinaryColor::On
+    );
+}
+
+fn draw_blended<T>(target: &mut T)
+where
+    T: ReadableTarget<Color = Rgb565>,
+    T::Error: Debug,
+{
+    let mut blended = target.blended();
+
+    blended.clear(Alpha::opaque(Rgb565::BLUE)).unwrap();
+    blended
+        .draw_iter([
+            Pixel(Point::new(0, 0), Alpha::new(Rgb565::RED, 0)),
+            Pixel(Point::new(1, 0), Alpha::opaque(Rgb565::RED)),
+            Pixel(Point::new(0, 1), (Rgb565::RED, 128).into()),
+            Pixel(Point::new(5, 5), Alpha::new(Rgb565::RED, 128)),
+        ])
+        .unwrap();
+}
+
+#[test]
+fn blended_works_on_any_readable_target() {
+    let mut packed = PackedFramebuffer::<Rgb565, _>::new(vec![0; 8], 2, 2).unwrap();
+    draw_blended(&mut packed);
+
+    let mut recorded = RecordingDisplay::new(Size::new(2, 2), Rgb565::BLACK);
+    draw_blended(&mut recorded);
+
+    let expected = [
+        Some(Rgb565::BLUE),
+        Some(Rgb565::RED),
+        Some(Rgb565::new(16, 0, 15)),
+        Some(Rgb565::BLUE),
+    ];
+    assert_eq!(packed.rows().flatten().collect::<Vec<_>>(), expected);
+    assert_eq!(recorded.rows().flatten().collect::<Vec<_>>(), expected);
+
+    // Opaque fills skip reading the background
+    assert_eq!(
+        recorded.take_ops()[0],
+        Op::FillSolid(recorded.bounding_box(), Rgb565::BLUE)
+    );
+    assert_eq!(recorded.out_of_bounds(), 0);
+}
+
+#[test]
+fn owned_translated_cropped_and_clipped_read_back() {
+    let mut display = gray_display(4, 3);
+    draw_gradient(&mut display);
+
+    let translated = display.owned_translated(Point::new(-1, 0));
+    assert_eq!(translated.get_pixel(Point::new(2, 1)), Some(Gray8::new(32)));
+    assert_eq!(translated.get_pixel(Point::new(0, 1)), None);
+
+    let display = translated.into_inner().into_inner();
+    let cropped = display.owned_cropped(&Rectangle::new(Point::new(2, 1), Size::new(5, 5)));
+    assert_eq!(cropped.get_pixel(Point::new(1, 1)), Some(Gray8::new(96)));
+    assert_eq!(cropped.get_pixel(Point::new(2, 0)), None);
+
+    let display = cropped.into_inner().into_inner();
+    let clipped = display.owned_clipped(&Rectangle::new(Point::zero(), Size::new(1, 1)));
+    assert_eq!(clipped.get_pixel(Point::new(3, 2)), Some(Gray8::new(96)));
+}
+
+//
+// Export
+//
+
+#[test]
+#[cfg(feature = "image-export")]
+fn png_snapshot() {
+    let mut display = RecordingDisplay::new(Size::new(4, 3), Rgb565::BLACK);
+
+    Rectangle::new(Point::new(1, 0), Size::new(2, 2))
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+        .draw(&mut display)
+        .unwrap();
+    Pixel(Point::new(3, 2), Rgb565::BLUE)
+        .draw(&mut display)
+        .unwrap();
+
+    assert_png_snapshot!("export_rgb565", &display);
+}
+
+/// Reads back as `None` on the diagonal, like a target with pixels it can't read.
+#[cfg(feature = "image-export")]
+struct Unreadable<T>(T);
+
+#[cfg(feature = "image-export")]
+impl<T> DrawTarget for Unreadable<T>
+where
+    T: DrawTarget,
+{
+    type Color = T::Color;
+    type Error = T::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.0.draw_iter(pixels)
+    }
+}
+
+#[cfg(feature = "image-export")]
+impl<T> Dimensions for Unreadable<T>
+where
+    T: Dimensions,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.0.bounding_box()
+    }
+}
+
+#[cfg(feature = "image-export")]
+impl<T> ReadableTarget for Unreadable<T>
+where
+    T: ReadableTarget,
+{
+    fn get_pixel(&self, point: Point) -> Option<Self::Color> {
+        (point.x != point.y)
+            .then(|| self.0.get_pixel(point))
+            .flatten()
+    }
+}
+
+#[test]
+#[cfg(feature = "image-export")]
+fn pbm_pads_rows_to_whole_bytes() {
+    let mut target = Unreadable(display(10, 2));
+    Pixel(Point::new(9, 0), BinaryColor::On)
+        .draw(&mut target)
+        .unwrap();
+
+    let mut pbm = Vec::new();
+    write_pbm(&target, BinaryColor::On, &mut pbm).unwrap();
+
+    assert_eq!(pbm, b"P4\n10 2\n\x80\x40\x40\x00");
+}
+
+#[test]
+#[cfg(feature = "image-export")]
+fn pgm_keeps_the_grey_levels() {
+    let mut target = Unreadable(RecordingDisplay::new(Size::new(2, 2), Gray4::BLACK));
+    Pixel(Point::new(1, 0), Gray4::new(7))
+        .draw(&mut target)
+        .unwrap();
+
+    let mut pgm = Vec::new();
+    write_pgm(&target, Gray4::WHITE, &mut pgm).unwrap();
+
+    assert_eq!(pgm, b"P5\n2 2\n15\n\x0f\x07\x00\x0f");
+}
+
+#[test]
+#[cfg(feature = "image-export")]
+fn ppm_writes_eight_bits_per_channel() {
+    let mut target = RecordingDisplay::new(Size::new(2, 1), Rgb565::BLACK);
+    Pixel(Point::new(1, 0), Rgb565::WHITE)
+        .draw(&mut target)
+        .unwrap();
+
+    let mut ppm = Vec::new();
+    write_ppm(&target, Rgb565::BLACK, &mut ppm).unwrap();
+
+    assert_eq!(ppm, b"P6\n2 1\n255\n\x00\x00\x00\xff\xff\xff");
+}
+
+#[test]
+#[cfg(feature = "image-export")]
+fn ppm_writes_the_background_for_unreadable_pixels() {
+    let mut target = Unreadable(RecordingDisplay::new(Size::new(2, 1), Rgb888::BLACK));
+    Pixel(Point::new(1, 0), Rgb888::new(4, 5, 6))
+        .draw(&mut target)
+        .unwrap();
+
+    let mut ppm = Vec::new();
+    write_ppm(&target, Rgb888::new(1, 2, 3), &mut ppm).unwrap();
+
+    assert_eq!(ppm, b"P6\n2 1\n255\n\x01\x02\x03\x04\x05\x06");
+}
+
+/// Splits a PNG into its chunks, checking the signature and that every length adds up.
+#[cfg(feature = "image-export")]
+fn png_chunks(mut png: &[u8]) -> Vec<(&[u8], &[u8])> {
+    assert_eq!(png[..8], *b"\x89PNG\r\n\x1a\n");
+    png = &png[8..];
+
+    let mut chunks = Vec::new();
+
+    while !png.is_empty() {
+        let len = u32::from_be_bytes(png[..4].try_into().unwrap()) as usize;
+        assert!(png.len() >= 12 + len, "chunk longer than the file");
+
+        chunks.push((&png[4..8], &png[8..8 + len]));
+        png = &png[12 + len..];
+    }
+
+    chunks
+}
+
+#[test]
+#[cfg(feature = "image-export")]
+fn png_stores_one_block_per_row() {
+    let mut target = Unreadable(RecordingDisplay::new(Size::new(2, 1), Rgb888::BLACK));
+    Pixel(Point::new(1, 0), Rgb888::new(4, 5, 6))
+        .draw(&mut target)
+        .unwrap();
+
+    let mut png = Vec::new();
+    write_png(&target, Rgb888::new(1, 2, 3), &mut png).unwrap();
+
+    assert_eq!(
+        png_chunks(&png),
+        [
+            (&b"IHDR"[..], &[0, 0, 0, 2, 0, 0, 0, 1, 8, 2, 0, 0, 0][..]),
+            (
+                b"IDAT",
+                &[
+                    0x78, 0x01, // zlib header
+                    1, 7, 0, 0xf8, 0xff, // final stored block of 7 bytes
+                    0, 1, 2, 3, 4, 5, 6, // unfiltered row
+                    0, 63, 0, 22, // Adler-32
+                ],
+            ),
+            (b"IEND", &[]),
+        ]
+    );
+    // The CRC of an empty IEND chunk is fixed
+    assert_eq!(png[png.len() - 4..], [0xae, 0x42, 0x60, 0x82]);
+}
+
+#[test]
+#[cfg(feature = "image-export")]
+fn png_of_an_empty_target_is_valid() {
+    let target = RecordingDisplay::new(Size::new(3, 0), Rgb888::BLACK);
+
+    let mut png = Vec::new();
+    write_png(&target, Rgb888::BLACK, &mut png).unwrap();
+
+    assert_eq!(
+        png_chunks(&png)[1],
+        (
+            &b"IDAT"[..],
+            &[0x78, 0x01, 1, 0, 0, 0xff, 0xff, 0, 0, 0, 1][..]
+        )
+    );
+}
+
+//
+// Terminal
+//
+
+#[cfg(feature = "terminal")]
+fn terminal_output<C>(terminal: &mut TerminalDisplay<C, Vec<u8>>) -> String
+where
+    C: TerminalColor,
+{
+    terminal.flush().unwrap();
+
+    String::from_utf8(core::mem::take(terminal.writer_mut())).unwrap()
+}
+
+#[test]
+#[cfg(feature = "terminal")]
+fn terminal_prints_half_blocks() {
+    let mut terminal = TerminalDisplay::with_writer(Size::new(5, 3), BinaryColor::Off, Vec::new());
+    draw_f(&mut terminal);
+
+    assert_eq!(terminal_output(&mut terminal), "███▀▀\n▀    \n");
+
+    let mut terminal = TerminalDisplay::with_writer(Size::new(4, 2), Gray4::BLACK, Vec::new());
+    terminal
+        .fill_contiguous(
+            &terminal.bounding_box(),
+            [0, 4, 8, 15, 0, 4, 8, 12].map(Gray4::new),
+        )
+        .unwrap();
+
+    assert_eq!(terminal_output(&mut terminal), " ░▒█\n");
+}
+
+#[test]
+#[cfg(feature = "terminal")]
+fn terminal_prints_rgb_colors() {
+    let mut terminal = TerminalDisplay::with_writer(Size::new(3, 1), Rgb888::BLACK, Vec::new());
+    Pixel(Point::new(2, 0), Rgb888::new(1, 2, 3))
+        .draw(&mut terminal)
+        .unwrap();
+
+    assert_eq!(
+        terminal_output(&mut terminal),
+        "\x1b[38;2;0;0;0;48;2;0;0;0m▀▀\x1b[38;2;1;2;3;48;2;0;0;0m▀\x1b[0m\n"
+    );
+}
+
+#[test]
+#[cfg(feature = "terminal")]
+fn terminal_redraws_changed_rows() {
+    let mut terminal = TerminalDisplay::with_writer(Size::new(2, 6), BinaryColor::Off, Vec::new())
+        .with_diffing(true);
+
+    assert_eq!(terminal_output(&mut terminal), "  \n  \n  \n");
+
+    Pixel(Point::new(1, 3), BinaryColor::On)
+        .draw(&mut terminal)
+        .unwrap();
+    assert_eq!(terminal_output(&mut terminal), "\x1b[3F\x1b[1E ▄\n\x1b[1E");
+
+    assert_eq!(terminal_output(&mut terminal), "\x1b[3F\x1b[3E");
+
+    terminal.invalidate();
+    assert_eq!(terminal_output(&mut terminal), "  \n ▄\n  \n");
+}
+
+//
+// Recorder
+//
+
+#[test]
+fn recorder_replays_onto_another_target() {
+    let mut recorder = display(5, 3).owned_recorder();
+    draw_f(&mut recorder.rotated(RotateAngle::Degrees180));
+    recorder.clear(BinaryColor::Off).unwrap();
+    draw_f(&mut recorder.rotated(RotateAngle::Degrees180));
+    recorder.flush().unwrap();
+
+    let calls = recorder.inner_mut().take_calls();
+    assert_eq!(calls.last(), Some(&DrawCall::Flush));
+    assert!(recorder.inner().calls().is_empty());
+
+    let recorded = recorder.into_inner().into_inner();
+
+    let mut replayed = display(5, 3);
+    replay(&calls, &mut replayed).unwrap();
+    assert_eq!(to_ascii(&replayed), to_ascii(&recorded));
+    assert_eq!(replayed.flushes(), 0);
+
+    let mut replayed = display(5, 3);
+    replay_flushing(&calls, &mut replayed).unwrap();
+    assert_eq!(replayed.flushes(), 1);
+}
+
+#[test]
+fn recorder_shows_per_pixel_traffic() {
+    let area = Rectangle::new(Point::new(1, 1), Size::new(2, 2));
+    let mut target = display(6, 4);
+    let mut calls = Vec::new();
+
+    target
+        .recorder(&mut calls)
+        .rotated(RotateAngle::Degrees90)
+        .fill_solid(&area, BinaryColor::On)
+        .unwrap();
+    assert_eq!(
+        DrawStats::of(&calls),
+        DrawStats {
+            calls: 1,
+            pixels: 4
+        }
+    );
+
+    calls.clear();
+    target
+        .recorder(&mut calls)
+        .scaled(Size::new(3, 2))
+        .fill_solid(&area, BinaryColor::On)
+        .unwrap();
+    assert_eq!(
+        DrawStats::of(&calls),
+        DrawStats {
+            calls: 1,
+            pixels: 16
+        }
+    );
+
+    calls.clear();
+    target.recorder(&mut calls).clear(BinaryColor::Off).unwrap();
+    assert_eq!(
+        calls,
+        [DrawCall::Clear(target.bounding_box(), BinaryColor::Off)]
+    );
+    assert_eq!(calls[0].pixels(), 24);
+
+    let mut recorder = target.owned_recorder();
+    recorder
+        .affine(AffineMatrix::shearing(AffineMatrix::ONE / 2, 0))
+        .fill_solid(&area, BinaryColor::On)
+        .unwrap();
+    assert!(matches!(recorder.inner().calls(), [DrawCall::DrawIter(_)]));
+    assert_eq!(
+        recorder.inner().stats(),
+        DrawStats {
+            calls: 1,
+            pixels: 4
+        }
+    );
+}
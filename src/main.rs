@@ -9,18 +9,41 @@ use embedded_graphics::{
 };
 
 use embedded_hal::i2c::I2c;
-use graphics::{Flushable, OwnedDrawTargetExt};
+use graphics::{BoxedFlushable, Flushable, OwnedDrawTargetExt, ReadableTarget};
 use serial::{HandlesI2C, OwnedTargetExt};
 use std::fmt;
-use std::{any, convert::Infallible, error};
-use std::{fmt::Debug, marker::PhantomData};
+use std::fmt::Debug;
+use std::{convert::Infallible, error};
+
+/// Implements `inner`, `inner_mut` and `into_inner` for a wrapper holding its parent in field `0`,
+/// e.g. `inner_accessors!(HandlerT<T, F> where T: ErrorType<Error = I2cCommError>);`.
+macro_rules! inner_accessors {
+    ($name:ident<$parent:ident $(, $param:ident)*> $(where $($bounds:tt)+)?) => {
+        impl<$parent $(, $param)*> $name<$parent $(, $param)*> $(where $($bounds)+)? {
+            pub fn inner(&self) -> &$parent {
+                &self.0
+            }
+
+            pub fn inner_mut(&mut self) -> &mut $parent {
+                &mut self.0
+            }
+
+            pub fn into_inner(self) -> $parent {
+                self.0
+            }
+        }
+    };
+}
 
 pub mod graphics;
 pub mod serial;
+#[cfg(test)]
+mod test_support;
 
 type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
 
 /// SPI communication error
+#[allow(dead_code)]
 #[derive(Debug)]
 struct CommError;
 
@@ -77,6 +100,18 @@ impl<SPI> OriginDimensions for ExampleDisplay<SPI> {
     }
 }
 
+impl<SPI> ReadableTarget for ExampleDisplay<SPI> {
+    fn get_pixel(&self, point: Point) -> Option<Gray8> {
+        if let Ok((x @ 0..=63, y @ 0..=63)) = point.try_into() {
+            let index: u32 = x + y * 64;
+
+            Some(Gray8::new(self.framebuffer[index as usize]))
+        } else {
+            None
+        }
+    }
+}
+
 struct DummySpi {}
 
 trait SpiWrite {
@@ -155,19 +190,20 @@ impl DummyI2c {
 }
 
 struct ExampleDevice<I2C> {
+    #[allow(dead_code)]
     iface: I2C,
 }
 
 impl<I2C> I2c for ExampleDevice<I2C> {
-    fn read(&mut self, address: u8, buffer: &mut [u8]) -> std::result::Result<(), Self::Error> {
+    fn read(&mut self, _address: u8, _buffer: &mut [u8]) -> std::result::Result<(), Self::Error> {
         todo!()
     }
 
-    fn write(&mut self, address: u8, bytes: &[u8]) -> std::result::Result<(), Self::Error> {
+    fn write(&mut self, _address: u8, _bytes: &[u8]) -> std::result::Result<(), Self::Error> {
         todo!()
     }
 
-    fn write_iter<B>(&mut self, address: u8, bytes: B) -> std::result::Result<(), Self::Error>
+    fn write_iter<B>(&mut self, _address: u8, _bytes: B) -> std::result::Result<(), Self::Error>
     where
         B: IntoIterator<Item = u8>,
     {
@@ -176,18 +212,18 @@ impl<I2C> I2c for ExampleDevice<I2C> {
 
     fn write_read(
         &mut self,
-        address: u8,
-        bytes: &[u8],
-        buffer: &mut [u8],
+        _address: u8,
+        _bytes: &[u8],
+        _buffer: &mut [u8],
     ) -> std::result::Result<(), Self::Error> {
         todo!()
     }
 
     fn write_iter_read<B>(
         &mut self,
-        address: u8,
-        bytes: B,
-        buffer: &mut [u8],
+        _address: u8,
+        _bytes: B,
+        _buffer: &mut [u8],
     ) -> std::result::Result<(), Self::Error>
     where
         B: IntoIterator<Item = u8>,
@@ -197,16 +233,16 @@ impl<I2C> I2c for ExampleDevice<I2C> {
 
     fn transaction<'a>(
         &mut self,
-        address: u8,
-        operations: &mut [embedded_hal::i2c::Operation<'a>],
+        _address: u8,
+        _operations: &mut [embedded_hal::i2c::Operation<'a>],
     ) -> std::result::Result<(), Self::Error> {
         todo!()
     }
 
     fn transaction_iter<'a, O>(
         &mut self,
-        address: u8,
-        operations: O,
+        _address: u8,
+        _operations: O,
     ) -> std::result::Result<(), Self::Error>
     where
         O: IntoIterator<Item = embedded_hal::i2c::Operation<'a>>,
@@ -255,6 +291,24 @@ fn main() -> Result<()> {
 
     circle.draw(&mut display)?;
 
+    // A screenshot of what was drawn so far, e.g. to attach to a bug report.
+    #[cfg(feature = "image-export")]
+    {
+        let mut screenshot = Vec::new();
+        graphics::write_pgm(&display, Gray8::BLACK, &mut screenshot)?;
+
+        log::info!("Screenshot: {} bytes", screenshot.len());
+    }
+
+    // Without hardware, the same drawing can be watched in the terminal.
+    #[cfg(feature = "terminal")]
+    {
+        let mut terminal = graphics::TerminalDisplay::new(Size::new(64, 64), Gray8::BLACK);
+
+        circle.draw(&mut terminal)?;
+        terminal.flush()?;
+    }
+
     // Calling `flush` here calls this directly on our instance of
     // `ExampleDisplay`.
     display.flush().unwrap();
@@ -264,7 +318,14 @@ fn main() -> Result<()> {
     //
     // This now has type `impl Flushable<Color = Gray8, Error = impl Debug>`.
     let type_erased = get_display(display)?;
-    type_erased.owned_noop_flushing();
+
+    // Boxing erases the remaining `Owned<FlushingT<...>>` type, so displays built from
+    // different adapter chains can be kept side by side.
+    let mut displays = [BoxedFlushable::new(type_erased.owned_noop_flushing())];
+
+    for display in displays.iter_mut() {
+        display.flush().unwrap();
+    }
 
     Ok(())
 }
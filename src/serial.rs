@@ -1,9 +1,11 @@
-use anyhow::Error;
 use embedded_hal::i2c::{AddressMode as EHalI2cAddressMode, ErrorType, I2c};
 use embedded_hal_0_2::blocking::i2c::{AddressMode, SevenBitAddress};
 
 use crate::I2cCommError;
 
+#[cfg(feature = "derive")]
+pub use owned_transform_derive::I2cTransformer as Transformer;
+
 /*
     Flushing<'a, T, F>:     Handler<'a, T, F>
     FlushingT<T, F>:        HandlerT<T, F>
@@ -12,9 +14,9 @@ use crate::I2cCommError;
 
 pub trait Transformer {
     type AddressMode: AddressMode;
-    type Error;
+    type Error: embedded_hal::i2c::Error;
 
-    type I2c<'a>: I2c<Self::AddressMode>
+    type I2c<'a>: I2c<Self::AddressMode, Error = Self::Error>
     where
         Self: 'a,
         <Self as Transformer>::AddressMode: EHalI2cAddressMode;
@@ -57,39 +59,51 @@ where
     }
 }
 
+inner_accessors!(HandlerT<T, F> where T: ErrorType<Error = I2cCommError>);
+
+impl<T, F> HandlerT<T, F>
+where
+    T: embedded_hal::i2c::ErrorType<Error = I2cCommError>,
+{
+    pub fn handler(&self) -> &F {
+        &self.1
+    }
+
+    pub fn set_handler(&mut self, handler: F) {
+        self.1 = handler;
+    }
+}
+
 pub struct Owned<T>(T);
 
 impl<T> Owned<T>
 where
     T: Transformer,
 {
-    fn new(mut transformer: T) -> Self {
+    fn new(transformer: T) -> Self {
         Self(transformer)
     }
 }
 
+inner_accessors!(Owned<T>);
+
 impl<T> I2c for Owned<T>
 where
     T: Transformer<AddressMode = SevenBitAddress>,
 {
-    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error>
-    where
-        <T as Transformer>::AddressMode: EHalI2cAddressMode,
-    {
-        self.0.transform().read(address, buffer);
-
-        Ok(())
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.0.transform().read(address, buffer)
     }
 
     fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
-        todo!()
+        self.0.transform().write(address, bytes)
     }
 
     fn write_iter<B>(&mut self, address: u8, bytes: B) -> Result<(), Self::Error>
     where
         B: IntoIterator<Item = u8>,
     {
-        todo!()
+        self.0.transform().write_iter(address, bytes)
     }
 
     fn write_read(
@@ -98,7 +112,7 @@ where
         bytes: &[u8],
         buffer: &mut [u8],
     ) -> Result<(), Self::Error> {
-        todo!()
+        self.0.transform().write_read(address, bytes, buffer)
     }
 
     fn write_iter_read<B>(
@@ -110,7 +124,7 @@ where
     where
         B: IntoIterator<Item = u8>,
     {
-        todo!()
+        self.0.transform().write_iter_read(address, bytes, buffer)
     }
 
     fn transaction<'a>(
@@ -118,14 +132,14 @@ where
         address: u8,
         operations: &mut [embedded_hal::i2c::Operation<'a>],
     ) -> Result<(), Self::Error> {
-        todo!()
+        self.0.transform().transaction(address, operations)
     }
 
     fn transaction_iter<'a, O>(&mut self, address: u8, operations: O) -> Result<(), Self::Error>
     where
         O: IntoIterator<Item = embedded_hal::i2c::Operation<'a>>,
     {
-        todo!()
+        self.0.transform().transaction_iter(address, operations)
     }
 }
 
@@ -148,7 +162,7 @@ impl<T> ErrorType for Owned<T>
 where
     T: Transformer,
 {
-    type Error = I2cCommError;
+    type Error = T::Error;
 }
 
 //
@@ -198,9 +212,7 @@ where
             handler,
         } = self;
 
-        let resp = (handler)(target);
-
-        resp
+        (handler)(target)
     }
 }
 
@@ -310,3 +322,6 @@ where
         HandlerT(self, handler).into_owned()
     }
 }
+
+#[cfg(test)]
+mod tests;
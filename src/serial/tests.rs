@@ -0,0 +1,207 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use embedded_hal::i2c::{ErrorType, I2c, Operation};
+
+use super::*;
+
+/// Nothing acknowledges this address.
+const MISSING: u8 = 0x7f;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Transfer {
+    Read(u8, usize),
+    Write(u8, Vec<u8>),
+}
+
+/// A bus logging every transfer into a shared log, reads fill the buffer with the address.
+#[derive(Clone, Default)]
+struct MockBus {
+    transfers: Rc<RefCell<Vec<Transfer>>>,
+}
+
+impl MockBus {
+    fn check(address: u8) -> Result<(), I2cCommError> {
+        if address == MISSING {
+            Err(I2cCommError::new("no acknowledge"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl ErrorType for MockBus {
+    type Error = I2cCommError;
+}
+
+impl I2c for MockBus {
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.transaction(address, &mut [Operation::Read(buffer)])
+    }
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.transaction(address, &mut [Operation::Write(bytes)])
+    }
+
+    fn write_iter<B>(&mut self, address: u8, bytes: B) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        let bytes: Vec<u8> = bytes.into_iter().collect();
+
+        self.write(address, &bytes)
+    }
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.transaction(
+            address,
+            &mut [Operation::Write(bytes), Operation::Read(buffer)],
+        )
+    }
+
+    fn write_iter_read<B>(
+        &mut self,
+        address: u8,
+        bytes: B,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        let bytes: Vec<u8> = bytes.into_iter().collect();
+
+        self.write_read(address, &bytes, buffer)
+    }
+
+    fn transaction<'a>(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'a>],
+    ) -> Result<(), Self::Error> {
+        Self::check(address)?;
+
+        for operation in operations {
+            match operation {
+                Operation::Read(buffer) => {
+                    buffer.fill(address);
+                    self.transfers
+                        .borrow_mut()
+                        .push(Transfer::Read(address, buffer.len()));
+                }
+                Operation::Write(bytes) => {
+                    self.transfers
+                        .borrow_mut()
+                        .push(Transfer::Write(address, bytes.to_vec()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn transaction_iter<'a, O>(&mut self, address: u8, operations: O) -> Result<(), Self::Error>
+    where
+        O: IntoIterator<Item = Operation<'a>>,
+    {
+        let mut operations: Vec<_> = operations.into_iter().collect();
+
+        self.transaction(address, &mut operations)
+    }
+}
+
+fn noop(_: &mut MockBus) -> Result<(), I2cCommError> {
+    Ok(())
+}
+
+#[test]
+fn owned_handler_delegates_every_method() {
+    let mock = MockBus::default();
+    let mut bus = mock.clone().owned_handler(noop);
+    let mut buffer = [0; 2];
+
+    bus.write(0x10, &[1, 2]).unwrap();
+    bus.write_iter(0x11, [3]).unwrap();
+    bus.read(0x12, &mut buffer).unwrap();
+    assert_eq!(buffer, [0x12; 2]);
+    bus.write_read(0x13, &[4], &mut buffer).unwrap();
+    assert_eq!(buffer, [0x13; 2]);
+    bus.write_iter_read(0x14, [5], &mut buffer[..1]).unwrap();
+    bus.transaction(0x15, &mut [Operation::Write(&[6])])
+        .unwrap();
+    bus.transaction_iter(0x16, [Operation::Write(&[7])])
+        .unwrap();
+
+    assert_eq!(
+        *mock.transfers.borrow(),
+        [
+            Transfer::Write(0x10, vec![1, 2]),
+            Transfer::Write(0x11, vec![3]),
+            Transfer::Read(0x12, 2),
+            Transfer::Write(0x13, vec![4]),
+            Transfer::Read(0x13, 2),
+            Transfer::Write(0x14, vec![5]),
+            Transfer::Read(0x14, 1),
+            Transfer::Write(0x15, vec![6]),
+            Transfer::Write(0x16, vec![7]),
+        ]
+    );
+}
+
+#[test]
+fn owned_handler_propagates_errors() {
+    let mock = MockBus::default();
+    let mut bus = mock.clone().owned_handler(noop);
+    let mut buffer = [0; 1];
+
+    assert!(bus.read(MISSING, &mut buffer).is_err());
+    assert!(bus.write(MISSING, &[1]).is_err());
+    assert!(bus.write_iter(MISSING, [1]).is_err());
+    assert!(bus.write_read(MISSING, &[1], &mut buffer).is_err());
+    assert!(bus.write_iter_read(MISSING, [1], &mut buffer).is_err());
+    assert!(bus.transaction(MISSING, &mut []).is_err());
+    assert!(bus.transaction_iter(MISSING, []).is_err());
+
+    assert!(mock.transfers.borrow().is_empty());
+}
+
+#[test]
+fn owned_handler_runs_the_handler_on_the_bus() {
+    type Handler = fn(&mut MockBus) -> Result<(), I2cCommError>;
+
+    let mock = MockBus::default();
+    let mut bus = mock
+        .clone()
+        .owned_handler((|bus: &mut MockBus| bus.write(0x20, &[0xff])) as Handler);
+
+    bus.handle().unwrap();
+    assert_eq!(
+        *mock.transfers.borrow(),
+        [Transfer::Write(0x20, vec![0xff])]
+    );
+
+    let mut failing =
+        MockBus::default().owned_handler((|bus: &mut MockBus| bus.write(MISSING, &[])) as Handler);
+    assert!(failing.handle().is_err());
+}
+
+#[test]
+fn owned_handler_exposes_the_bus_and_the_handler() {
+    type Handler = fn(&mut MockBus) -> Result<(), I2cCommError>;
+
+    let mock = MockBus::default();
+    let mut bus = mock.clone().owned_handler(noop as Handler);
+
+    bus.inner_mut()
+        .set_handler(|bus: &mut MockBus| bus.write(0x21, &[]));
+    bus.handle().unwrap();
+    assert_eq!(*mock.transfers.borrow(), [Transfer::Write(0x21, vec![])]);
+
+    bus.inner_mut().inner_mut().write(0x22, &[1]).unwrap();
+    let inner: MockBus = bus.into_inner().into_inner();
+    assert_eq!(inner.transfers.borrow().len(), 2);
+}
@@ -0,0 +1,318 @@
+//! Helpers for testing draw targets: an in-memory [`RecordingDisplay`] and golden-image
+//! snapshots.
+//!
+//! Snapshots live in `tests/snapshots`. Monochrome and grayscale output is stored as ASCII art
+//! (`.txt`), colour output as PNG (with the `image-export` feature). A mismatch writes the actual
+//! output next to the golden file with a `.new` suffix; run the tests with `UPDATE_SNAPSHOTS=1` to
+//! accept it.
+
+use std::path::PathBuf;
+use std::vec::Vec;
+
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::pixelcolor::{BinaryColor, Gray2, Gray4, Gray8, GrayColor};
+#[cfg(feature = "image-export")]
+use embedded_graphics::pixelcolor::{Rgb888, RgbColor};
+use embedded_graphics::prelude::{Dimensions, OriginDimensions, PixelColor, Point, Size};
+use embedded_graphics::primitives::{PointsIter, Rectangle};
+use embedded_graphics::Pixel;
+
+#[cfg(feature = "image-export")]
+use crate::graphics::write_png;
+use crate::graphics::{Flushable, ReadableTarget};
+
+pub const UPDATE_SNAPSHOTS: &str = "UPDATE_SNAPSHOTS";
+
+/// A drawing operation as received by a [`RecordingDisplay`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Op<C> {
+    DrawIter { pixels: usize },
+    FillContiguous(Rectangle),
+    FillSolid(Rectangle, C),
+    Clear(C),
+    Flush,
+}
+
+/// A display kept in memory that also logs every operation it receives.
+pub struct RecordingDisplay<C> {
+    size: Size,
+    pixels: Vec<C>,
+    ops: Vec<Op<C>>,
+    out_of_bounds: usize,
+}
+
+impl<C> RecordingDisplay<C>
+where
+    C: PixelColor,
+{
+    pub fn new(size: Size, background: C) -> Self {
+        Self {
+            size,
+            pixels: vec![background; size.width as usize * size.height as usize],
+            ops: Vec::new(),
+            out_of_bounds: 0,
+        }
+    }
+
+    pub fn ops(&self) -> &[Op<C>] {
+        &self.ops
+    }
+
+    /// Returns the operations logged so far and starts a new log.
+    pub fn take_ops(&mut self) -> Vec<Op<C>> {
+        core::mem::take(&mut self.ops)
+    }
+
+    /// Number of pixels drawn outside the display, which targets are expected to discard.
+    pub fn out_of_bounds(&self) -> usize {
+        self.out_of_bounds
+    }
+
+    pub fn flushes(&self) -> usize {
+        self.ops.iter().filter(|op| **op == Op::Flush).count()
+    }
+
+    fn set(&mut self, point: Point, color: C) {
+        if self.bounding_box().contains(point) {
+            let index = point.y as usize * self.size.width as usize + point.x as usize;
+            self.pixels[index] = color;
+        } else {
+            self.out_of_bounds += 1;
+        }
+    }
+}
+
+impl<C> DrawTarget for RecordingDisplay<C>
+where
+    C: PixelColor,
+{
+    type Color = C;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let mut count = 0;
+
+        for Pixel(point, color) in pixels {
+            self.set(point, color);
+            count += 1;
+        }
+
+        self.ops.push(Op::DrawIter { pixels: count });
+
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        for (point, color) in area.points().zip(colors) {
+            self.set(point, color);
+        }
+
+        self.ops.push(Op::FillContiguous(*area));
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        for point in area.points() {
+            self.set(point, color);
+        }
+
+        self.ops.push(Op::FillSolid(*area, color));
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.pixels.fill(color);
+        self.ops.push(Op::Clear(color));
+
+        Ok(())
+    }
+}
+
+impl<C> OriginDimensions for RecordingDisplay<C> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<C> Flushable for RecordingDisplay<C>
+where
+    C: PixelColor,
+{
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.ops.push(Op::Flush);
+
+        Ok(())
+    }
+}
+
+impl<C> ReadableTarget for RecordingDisplay<C>
+where
+    C: PixelColor,
+{
+    fn get_pixel(&self, point: Point) -> Option<Self::Color> {
+        self.bounding_box()
+            .contains(point)
+            .then(|| self.pixels[point.y as usize * self.size.width as usize + point.x as usize])
+    }
+}
+
+/// Colours with a one character representation for ASCII snapshots.
+pub trait AsciiColor: PixelColor {
+    fn to_char(self) -> char;
+}
+
+impl AsciiColor for BinaryColor {
+    fn to_char(self) -> char {
+        if self.is_on() {
+            '#'
+        } else {
+            '.'
+        }
+    }
+}
+
+/// Grey levels are written as a hexadecimal digit, `0` is black and `F` white.
+impl AsciiColor for Gray2 {
+    fn to_char(self) -> char {
+        hex_digit(self.luma() * 5)
+    }
+}
+
+impl AsciiColor for Gray4 {
+    fn to_char(self) -> char {
+        hex_digit(self.luma())
+    }
+}
+
+impl AsciiColor for Gray8 {
+    fn to_char(self) -> char {
+        hex_digit(self.luma() >> 4)
+    }
+}
+
+fn hex_digit(value: u8) -> char {
+    char::from_digit(value as u32, 16)
+        .unwrap()
+        .to_ascii_uppercase()
+}
+
+/// Renders `target` one line per row.
+pub fn to_ascii<T>(target: &T) -> String
+where
+    T: ReadableTarget,
+    T::Color: AsciiColor,
+{
+    let bbox = target.bounding_box();
+    let mut ascii = String::new();
+
+    for (index, point) in bbox.points().enumerate() {
+        ascii.push(target.get_pixel(point).map_or('?', AsciiColor::to_char));
+
+        if (index + 1) % bbox.size.width as usize == 0 {
+            ascii.push('\n');
+        }
+    }
+
+    ascii
+}
+
+#[cfg(feature = "image-export")]
+pub fn to_png<T>(target: &T) -> Vec<u8>
+where
+    T: ReadableTarget,
+    T::Color: Into<Rgb888> + RgbColor,
+{
+    let mut png = Vec::new();
+    write_png(target, T::Color::BLACK, &mut png).unwrap();
+
+    png
+}
+
+/// Compares `actual` with the golden file `tests/snapshots/<name>`, see the module docs.
+#[track_caller]
+pub fn assert_snapshot_bytes(name: &str, actual: &[u8]) {
+    let path = snapshot_path(name);
+    let mut new = path.clone().into_os_string();
+    new.push(".new");
+
+    if update_snapshots() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, actual).unwrap();
+        let _ = std::fs::remove_file(&new);
+
+        return;
+    }
+
+    match std::fs::read(&path) {
+        Ok(expected) if expected == actual => {
+            let _ = std::fs::remove_file(&new);
+        }
+        expected => {
+            std::fs::write(&new, actual).unwrap();
+
+            match (expected, std::str::from_utf8(actual)) {
+                (Ok(expected), Ok(actual)) => panic!(
+                    "snapshot `{}` differs, set {} to accept\nexpected:\n{}\nactual:\n{}",
+                    name,
+                    UPDATE_SNAPSHOTS,
+                    String::from_utf8_lossy(&expected),
+                    actual
+                ),
+                (Ok(_), Err(_)) => panic!(
+                    "snapshot `{}` differs, see {:?}, set {} to accept",
+                    name, new, UPDATE_SNAPSHOTS
+                ),
+                (Err(err), _) => panic!(
+                    "snapshot `{}` can't be read ({}), set {} to create it",
+                    name, err, UPDATE_SNAPSHOTS
+                ),
+            }
+        }
+    }
+}
+
+/// Set to anything but an empty string or `0`.
+fn update_snapshots() -> bool {
+    std::env::var(UPDATE_SNAPSHOTS).is_ok_and(|value| !value.is_empty() && value != "0")
+}
+
+fn snapshot_path(name: &str) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "tests", "snapshots", name]
+        .iter()
+        .collect()
+}
+
+/// `assert_snapshot!("name", &target)` compares the ASCII art of a monochrome or grayscale
+/// target with `tests/snapshots/name.txt`.
+macro_rules! assert_snapshot {
+    ($name:expr, $target:expr) => {
+        $crate::test_support::assert_snapshot_bytes(
+            &format!("{}.txt", $name),
+            $crate::test_support::to_ascii($target).as_bytes(),
+        )
+    };
+}
+
+/// `assert_png_snapshot!("name", &target)` compares a colour target with
+/// `tests/snapshots/name.png`.
+#[cfg(feature = "image-export")]
+macro_rules! assert_png_snapshot {
+    ($name:expr, $target:expr) => {
+        $crate::test_support::assert_snapshot_bytes(
+            &format!("{}.png", $name),
+            &$crate::test_support::to_png($target),
+        )
+    };
+}
+
+#[cfg(feature = "image-export")]
+pub(crate) use assert_png_snapshot;
+pub(crate) use assert_snapshot;